@@ -0,0 +1,64 @@
+use clap::Args;
+use devices::{
+    DeviceInfo,
+    Selector,
+};
+
+/// Device filters shared by subcommands.
+#[derive(Args, Default)]
+pub struct Filter {
+    /// Only include devices matched by a selector, such as `usb vid=046d`.
+    #[arg(long)]
+    select: Option<Selector>,
+
+    /// Only include devices on this bus, such as `pci` or `usb`.
+    #[arg(long)]
+    bus: Option<String>,
+
+    /// Only include devices with this vendor id (hex) or vendor name.
+    #[arg(long)]
+    vendor: Option<String>,
+
+    /// Only include devices with this product id (hex) or product name.
+    #[arg(long)]
+    product: Option<String>,
+
+    /// Only include devices with this class id (hex) or class name.
+    #[arg(long)]
+    class: Option<String>,
+}
+
+/// Matches a hex id such as `046d` or `0x046d`, or a case-insensitive part of a name.
+fn matches(pattern: &str, id: Option<u16>, name: &str) -> bool {
+    let hex = pattern.trim_start_matches("0x");
+
+    if let Ok(pattern_id) = u16::from_str_radix(hex, 16) {
+        if id == Some(pattern_id) {
+            return true;
+        }
+    }
+
+    name.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+impl Filter {
+    pub fn matches(&self, device: &DeviceInfo) -> bool {
+        self.select.as_ref().map_or(true, |s| s.matches(device))
+            && self
+                .bus
+                .as_ref()
+                .map_or(true, |b| b.eq_ignore_ascii_case(device.path().bus()))
+            && self
+                .vendor
+                .as_ref()
+                .map_or(true, |v| matches(v, Some(device.vendor_id()), device.vendor()))
+            && self
+                .product
+                .as_ref()
+                .map_or(true, |p| matches(p, Some(device.product_id()), device.product()))
+            && self
+                .class
+                .as_ref()
+                .map_or(true, |c| matches(c, device.class_id(), device.class()))
+    }
+}
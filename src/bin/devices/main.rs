@@ -0,0 +1,259 @@
+//! Command-line interface for the `devices` crate.
+
+mod filter;
+mod output;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::ExitCode,
+    thread,
+    time::Duration,
+};
+
+use clap::{
+    Parser,
+    Subcommand,
+    ValueEnum,
+};
+use devices::{
+    export,
+    DeviceInfo,
+    DevicePath,
+    Devices,
+    Error,
+};
+
+use crate::{
+    filter::Filter,
+    output::Format,
+};
+
+/// List and inspect connected devices.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List connected devices.
+    List {
+        #[command(flatten)]
+        filter: Filter,
+
+        /// Output format.
+        #[arg(short, long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+
+    /// Show connected devices by topology.
+    Tree {
+        #[command(flatten)]
+        filter: Filter,
+    },
+
+    /// Show details of a single device.
+    Show {
+        /// Path of the device, such as `pci:0000:00:1f.3` or `usb:1-2.3`.
+        path: DevicePath,
+
+        /// Output format.
+        #[arg(short, long, value_enum, default_value_t = Format::Plain)]
+        format: Format,
+    },
+
+    /// Print devices as they are connected and disconnected.
+    Watch {
+        #[command(flatten)]
+        filter: Filter,
+
+        /// Polling interval in seconds, at least 1.
+        #[arg(short, long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+
+    /// Compare two snapshots created by `export`.
+    ///
+    /// The format of each snapshot is detected from its extension, defaulting to JSON.
+    Diff {
+        /// The older snapshot.
+        a: PathBuf,
+
+        /// The newer snapshot.
+        b: PathBuf,
+    },
+
+    /// Export connected devices as a snapshot.
+    Export {
+        #[command(flatten)]
+        filter: Filter,
+
+        /// Snapshot format.
+        #[arg(short, long, value_enum, default_value_t = SnapshotFormat::Json)]
+        format: SnapshotFormat,
+
+        /// Write the snapshot to a file instead of standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Snapshot format.
+#[derive(Clone, Copy, ValueEnum)]
+enum SnapshotFormat {
+    /// JSON.
+    Json,
+
+    /// YAML.
+    Yaml,
+
+    /// CSV.
+    Csv,
+}
+
+impl SnapshotFormat {
+    fn from_extension(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => Self::Yaml,
+            Some("csv") => Self::Csv,
+            _ => Self::Json,
+        }
+    }
+
+    fn encode(self, devices: &[DeviceInfo]) -> Result<String, Error> {
+        match self {
+            Self::Json => export::to_json(devices).map(|s| s + "\n"),
+            Self::Yaml => export::to_yaml(devices),
+            Self::Csv => export::to_csv(devices),
+        }
+    }
+
+    fn decode(self, contents: &str) -> Result<Vec<DeviceInfo>, Error> {
+        match self {
+            Self::Json => export::from_json(contents),
+            Self::Yaml => export::from_yaml(contents),
+            Self::Csv => export::from_csv(contents),
+        }
+    }
+}
+
+fn get(filter: &Filter) -> Result<Vec<DeviceInfo>, String> {
+    let devices = Devices::all().map_err(|e| format!("could not list devices: {e}"))?;
+
+    Ok(devices.into_iter().filter(|d| filter.matches(d)).collect())
+}
+
+fn find(path: &DevicePath) -> Result<DeviceInfo, String> {
+    get(&Filter::default())?
+        .into_iter()
+        .find(|d| d.path() == path)
+        .ok_or_else(|| format!("no device at {path}"))
+}
+
+fn read_snapshot(path: &Path) -> Result<Vec<DeviceInfo>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("could not read {}: {e}", path.display()))?;
+
+    SnapshotFormat::from_extension(path)
+        .decode(&contents)
+        .map_err(|e| format!("could not parse {}: {e}", path.display()))
+}
+
+fn by_path(devices: Vec<DeviceInfo>) -> BTreeMap<DevicePath, DeviceInfo> {
+    devices.into_iter().map(|d| (d.path().clone(), d)).collect()
+}
+
+fn diff(a: &[DeviceInfo], b: &[DeviceInfo]) -> Vec<String> {
+    let a = by_path(a.to_vec());
+    let b = by_path(b.to_vec());
+
+    let mut lines = Vec::new();
+
+    for (path, device) in &a {
+        match b.get(path) {
+            None => lines.push(format!("- {}", output::summary(device))),
+            Some(other) if other != device => {
+                lines.push(format!("~ {}", output::summary(device)));
+                lines.push(format!("  -> {}", output::summary(other)));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (path, device) in &b {
+        if !a.contains_key(path) {
+            lines.push(format!("+ {}", output::summary(device)));
+        }
+    }
+
+    lines
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    match cli.command {
+        Command::List { filter, format } => {
+            print!("{}", output::list(&get(&filter)?, format));
+        }
+        Command::Tree { filter } => {
+            print!("{}", output::tree(&get(&filter)?));
+        }
+        Command::Show { path, format } => {
+            print!("{}", output::show(&find(&path)?, format));
+        }
+        Command::Watch { filter, interval } => {
+            let mut previous = get(&filter)?;
+            print!("{}", output::list(&previous, Format::Plain));
+
+            loop {
+                thread::sleep(Duration::from_secs(interval));
+
+                let current = get(&filter)?;
+
+                for line in diff(&previous, &current) {
+                    println!("{line}");
+                }
+
+                previous = current;
+            }
+        }
+        Command::Diff { a, b } => {
+            for line in diff(&read_snapshot(&a)?, &read_snapshot(&b)?) {
+                println!("{line}");
+            }
+        }
+        Command::Export {
+            filter,
+            format,
+            output,
+        } => {
+            let snapshot = format
+                .encode(&get(&filter)?)
+                .map_err(|e| format!("could not export devices: {e}"))?;
+
+            match output {
+                Some(path) => fs::write(&path, snapshot)
+                    .map_err(|e| format!("could not write {}: {e}", path.display()))?,
+                None => print!("{snapshot}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("devices: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+};
+
+use clap::ValueEnum;
+use devices::{
+    export,
+    DeviceInfo,
+    DevicePath,
+    Devices,
+};
+
+/// Output format.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Aligned columns.
+    Table,
+
+    /// JSON.
+    Json,
+
+    /// One device per line.
+    Plain,
+}
+
+/// Formats a device as a single line.
+pub fn summary(device: &DeviceInfo) -> String {
+    format!(
+        "{} {:04x}:{:04x} {} {}",
+        device.path(),
+        device.vendor_id(),
+        device.product_id(),
+        device.vendor(),
+        device.product()
+    )
+}
+
+fn json(devices: &[DeviceInfo]) -> String {
+    let mut json = export::to_json(devices).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+fn table(devices: &[DeviceInfo]) -> String {
+    let rows: Vec<[String; 5]> = devices
+        .iter()
+        .map(|d| {
+            [
+                d.path().to_string(),
+                format!("{:04x}:{:04x}", d.vendor_id(), d.product_id()),
+                d.class().to_owned(),
+                d.vendor().to_owned(),
+                d.product().to_owned(),
+            ]
+        })
+        .collect();
+
+    let header = ["PATH", "ID", "CLASS", "VENDOR", "PRODUCT"].map(str::to_owned);
+
+    let mut widths = [0; 5];
+
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+
+    for row in std::iter::once(&header).chain(&rows) {
+        let mut line = String::new();
+
+        for (width, cell) in widths.iter().zip(row) {
+            let _ = write!(line, "{cell:width$}  ");
+        }
+
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Formats a list of devices.
+pub fn list(devices: &[DeviceInfo], format: Format) -> String {
+    match format {
+        Format::Table => table(devices),
+        Format::Json => json(devices),
+        Format::Plain => devices.iter().map(|d| summary(d) + "\n").collect(),
+    }
+}
+
+/// Formats all properties of a device.
+pub fn show(device: &DeviceInfo, format: Format) -> String {
+    if let Format::Json = format {
+        return json(std::slice::from_ref(device));
+    }
+
+    let mut out = String::new();
+
+    let _ = writeln!(out, "Path:         {}", device.path());
+    let _ = writeln!(out, "ID:           {}", device.id());
+    let _ = writeln!(out, "Class:        {}", device.class());
+    let _ = writeln!(out, "Vendor:       {} ({:04x})", device.vendor(), device.vendor_id());
+    let _ = writeln!(out, "Product:      {} ({:04x})", device.product(), device.product_id());
+
+    if let Some(id) = device.class_id() {
+        let _ = writeln!(out, "Class ID:     {id:04x}");
+    }
+
+    if let Some(manufacturer) = device.manufacturer() {
+        let _ = writeln!(out, "Manufacturer: {manufacturer}");
+    }
+
+    if let Some(serial) = device.serial() {
+        let _ = writeln!(out, "Serial:       {serial}");
+    }
+
+    if let Some(node) = device.numa_node() {
+        let _ = writeln!(out, "NUMA node:    {node}");
+    }
+
+    if let Some(cpus) = device.local_cpus() {
+        let cpus: Vec<String> = cpus.iter().map(ToString::to_string).collect();
+        let _ = writeln!(out, "Local CPUs:   {}", cpus.join(","));
+    }
+
+    if !device.compatible().is_empty() {
+        let _ = writeln!(out, "Compatible:   {}", device.compatible().join(", "));
+    }
+
+    if let Some(hid) = device.acpi_hid() {
+        let _ = writeln!(out, "ACPI HID:     {hid}");
+    }
+
+    for resource in device.resources() {
+        let _ = writeln!(
+            out,
+            "Resource {}:   {:#x}-{:#x} ({} bytes)",
+            resource.index(),
+            resource.start(),
+            resource.end(),
+            resource.size()
+        );
+    }
+
+    if !device.udev_tags().is_empty() {
+        let _ = writeln!(out, "udev tags:    {}", device.udev_tags().join(", "));
+    }
+
+    if !device.udev_properties().is_empty() {
+        let _ = writeln!(out, "udev properties:");
+
+        for (key, value) in device.udev_properties() {
+            let _ = writeln!(out, "  {key}={value}");
+        }
+    }
+
+    out
+}
+
+/// Formats devices as a tree, nesting each device below its parent.
+pub fn tree(devices: &[DeviceInfo]) -> String {
+    let mut children: BTreeMap<Option<DevicePath>, Vec<&DeviceInfo>> = BTreeMap::new();
+
+    for device in devices {
+        let parent = Devices::parent(device.path())
+            .ok()
+            .flatten()
+            .filter(|p| devices.iter().any(|d| d.path() == p));
+
+        children.entry(parent).or_default().push(device);
+    }
+
+    let mut out = String::new();
+    let mut stack: Vec<(&DeviceInfo, usize)> =
+        children.get(&None).into_iter().flatten().rev().map(|d| (*d, 0)).collect();
+
+    while let Some((device, depth)) = stack.pop() {
+        let _ = writeln!(out, "{}{}", "  ".repeat(depth), summary(device));
+
+        if let Some(nested) = children.get(&Some(device.path().clone())) {
+            stack.extend(nested.iter().rev().map(|d| (*d, depth + 1)));
+        }
+    }
+
+    out
+}
@@ -0,0 +1,103 @@
+use crate::info::DeviceInfo;
+
+/// A partition of a block device.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Partition {
+    pub(crate) name: String,
+    pub(crate) number: u32,
+    pub(crate) start: u64,
+    pub(crate) size: u64,
+}
+
+impl Partition {
+    /// Returns the kernel name of the partition, such as `sda1` or `nvme0n1p1`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the partition number.
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    /// Returns the offset of the partition from the start of the disk in bytes.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the size of the partition in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// A block storage device and the controller or USB device that provides it.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BlockDevice {
+    pub(crate) device: DeviceInfo,
+
+    pub(crate) name: String,
+    pub(crate) size: u64,
+    pub(crate) model: Option<String>,
+    pub(crate) serial: Option<String>,
+    pub(crate) wwn: Option<String>,
+    pub(crate) rotational: bool,
+    pub(crate) removable: bool,
+    pub(crate) partitions: Vec<Partition>,
+}
+
+impl BlockDevice {
+    /// Returns the controller or USB device that provides the block device.
+    pub fn device(&self) -> &DeviceInfo {
+        &self.device
+    }
+
+    /// Returns the kernel name of the block device, such as `sda` or `nvme0n1`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the device file, such as `/dev/sda`.
+    pub fn devnode(&self) -> String {
+        format!("/dev/{}", self.name)
+    }
+
+    /// Returns the size of the block device in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the model name of the disk, if known.
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Returns the serial number of the disk, if known.
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+
+    /// Returns the World Wide Name of the disk, if known.
+    pub fn wwn(&self) -> Option<&str> {
+        self.wwn.as_deref()
+    }
+
+    /// Returns `true` if the disk is rotational, such as a hard disk drive.
+    pub fn rotational(&self) -> bool {
+        self.rotational
+    }
+
+    /// Returns `true` if the media is removable.
+    pub fn removable(&self) -> bool {
+        self.removable
+    }
+
+    /// Returns the partitions of the block device.
+    pub fn partitions(&self) -> &[Partition] {
+        &self.partitions
+    }
+}
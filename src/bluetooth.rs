@@ -0,0 +1,79 @@
+use crate::info::DeviceInfo;
+
+/// A Bluetooth HCI controller, along with the device that provides it.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BluetoothController {
+    pub(crate) name: String,
+    pub(crate) device: Option<DeviceInfo>,
+
+    pub(crate) address: Option<String>,
+    pub(crate) manufacturer: Option<u16>,
+    pub(crate) hci_version: Option<u8>,
+    pub(crate) hci_revision: Option<u16>,
+}
+
+impl BluetoothController {
+    /// Returns the kernel name of the controller, such as `hci0`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the USB, PCI or platform device that provides the controller, if known.
+    pub fn device(&self) -> Option<&DeviceInfo> {
+        self.device.as_ref()
+    }
+
+    /// Returns the public address of the controller, such as `00:1A:7D:DA:71:13`, if known.
+    /// # Note
+    /// On Linux this is read through an HCI socket, falling back to debugfs.
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    /// Returns the Bluetooth SIG company identifier of the controller's manufacturer, if known.
+    /// # Note
+    /// On Linux this is read through an HCI socket while the controller is powered on, falling back to debugfs.
+    pub fn manufacturer(&self) -> Option<u16> {
+        self.manufacturer
+    }
+
+    /// Returns the HCI version of the controller, if known.
+    /// # Note
+    /// On Linux this is read through an HCI socket while the controller is powered on, falling back to debugfs.
+    pub fn hci_version(&self) -> Option<u8> {
+        self.hci_version
+    }
+
+    /// Returns the HCI revision of the controller, if known.
+    /// # Note
+    /// On Linux this is read through an HCI socket while the controller is powered on, falling back to debugfs.
+    pub fn hci_revision(&self) -> Option<u16> {
+        self.hci_revision
+    }
+
+    /// Returns the Bluetooth core specification version matching the HCI version, such as `5.3`.
+    pub fn bluetooth_version(&self) -> Option<&'static str> {
+        let version = match self.hci_version? {
+            0 => "1.0b",
+            1 => "1.1",
+            2 => "1.2",
+            3 => "2.0",
+            4 => "2.1",
+            5 => "3.0",
+            6 => "4.0",
+            7 => "4.1",
+            8 => "4.2",
+            9 => "5.0",
+            10 => "5.1",
+            11 => "5.2",
+            12 => "5.3",
+            13 => "5.4",
+            14 => "6.0",
+            _ => return None,
+        };
+
+        Some(version)
+    }
+}
@@ -0,0 +1,331 @@
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    fmt,
+};
+
+use crate::{
+    DeviceInfo,
+    DevicePath,
+};
+
+/// Devices that cannot be told apart by their vendor, product and serial number,
+/// such as several identical USB-serial adapters without serial numbers.
+///
+/// Members are labeled and ordered by where they are attached, so that the adapter on port 3
+/// of a hub keeps its label across sessions, as long as the same ports are used.
+/// Use [`DuplicateGroup::find`] or [`Devices::duplicates`](crate::Devices::duplicates)
+/// to find groups.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DuplicateGroup {
+    pub(crate) bus: String,
+    pub(crate) vendor_id: u16,
+    pub(crate) product_id: u16,
+    pub(crate) serial: Option<String>,
+    pub(crate) members: Vec<DuplicateMember>,
+}
+
+/// A device within a [`DuplicateGroup`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DuplicateMember {
+    pub(crate) index: usize,
+    pub(crate) label: String,
+    pub(crate) device: DeviceInfo,
+}
+
+impl DuplicateGroup {
+    /// Groups devices with the same bus, vendor id, product id and serial number.
+    ///
+    /// Only groups with more than one device are returned. Devices without vendor and product ids,
+    /// such as most platform devices, are never grouped, as their names already tell them apart.
+    /// Members are ordered by their path, which is where they are attached, such as USB port
+    /// `1-2.3` or PCI address `0000:03:00.0`. Numbers within paths are compared by value,
+    /// so port `10` comes after port `9`.
+    pub fn find(devices: &[DeviceInfo]) -> Vec<Self> {
+        type Key<'a> = (&'static str, u16, u16, Option<&'a str>);
+
+        let mut groups: BTreeMap<Key, Vec<&DeviceInfo>> = BTreeMap::new();
+
+        for device in devices {
+            if device.vendor_id() == 0 && device.product_id() == 0 {
+                continue;
+            }
+
+            let key = (
+                device.path().bus(),
+                device.vendor_id(),
+                device.product_id(),
+                device.serial(),
+            );
+
+            groups.entry(key).or_default().push(device);
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, devices)| devices.len() > 1)
+            .map(|((bus, vendor_id, product_id, serial), mut devices)| {
+                devices.sort_by(|a, b| location_cmp(a.path(), b.path()));
+
+                let prefix = match serial {
+                    Some(serial) => format!("{bus}:{vendor_id:04x}:{product_id:04x}:{serial}"),
+                    None => format!("{bus}:{vendor_id:04x}:{product_id:04x}"),
+                };
+
+                let members = devices
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, device)| DuplicateMember {
+                        index: i + 1,
+                        label: format!("{prefix}@{}", address(device.path())),
+                        device: device.clone(),
+                    })
+                    .collect();
+
+                Self {
+                    bus: bus.to_owned(),
+                    vendor_id,
+                    product_id,
+                    serial: serial.map(str::to_owned),
+                    members,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the bus of the devices, such as `usb`.
+    pub fn bus(&self) -> &str {
+        &self.bus
+    }
+
+    /// Returns the id of the devices' vendor.
+    pub fn vendor_id(&self) -> u16 {
+        self.vendor_id
+    }
+
+    /// Returns the devices' product id.
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+
+    /// Returns the serial number the devices share, if any.
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+
+    /// Returns the devices, ordered by location.
+    pub fn members(&self) -> &[DuplicateMember] {
+        &self.members
+    }
+
+    /// Returns the device with the given index, starting from `1`.
+    pub fn get(&self, index: usize) -> Option<&DuplicateMember> {
+        self.members.iter().find(|m| m.index == index)
+    }
+}
+
+impl DuplicateMember {
+    /// Returns the position of the device within its group, starting from `1`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a label for the device, such as `usb:0403:6001@1-2.3`.
+    ///
+    /// The label is made of the bus, vendor id, product id, serial number if any, and the address
+    /// of the device on its bus, so it does not change when other devices are connected.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the device.
+    pub fn device(&self) -> &DeviceInfo {
+        &self.device
+    }
+}
+
+impl fmt::Display for DuplicateMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+/// Returns the address of a device on its bus, such as `1-2.3` for `usb:1-2.3`.
+fn address(path: &DevicePath) -> String {
+    let path = path.to_string();
+
+    match path.split_once(':') {
+        Some((_, address)) => address.to_owned(),
+        None => path,
+    }
+}
+
+/// Orders paths by location, comparing numbers within device names by value.
+fn location_cmp(a: &DevicePath, b: &DevicePath) -> Ordering {
+    match (a, b) {
+        (DevicePath::PCI { .. }, DevicePath::PCI { .. })
+        | (DevicePath::USB { .. }, DevicePath::USB { .. }) => a.cmp(b),
+        _ => natural_cmp(&a.to_string(), &b.to_string()).then_with(|| a.cmp(b)),
+    }
+}
+
+/// Compares strings, treating runs of digits as numbers.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a;
+    let mut b = b;
+
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+
+        if x.is_ascii_digit() && y.is_ascii_digit() {
+            let a_len = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+            let b_len = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+
+            let a_num = a[..a_len].trim_start_matches('0');
+            let b_num = b[..b_len].trim_start_matches('0');
+
+            let ordering = a_num.len().cmp(&b_num.len()).then_with(|| a_num.cmp(b_num));
+
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+
+            a = &a[a_len..];
+            b = &b[b_len..];
+        } else {
+            if x != y {
+                return x.cmp(&y);
+            }
+
+            a = &a[x.len_utf8()..];
+            b = &b[y.len_utf8()..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn device(path: DevicePath, serial: Option<&str>) -> DeviceInfo {
+        DeviceInfo {
+            path,
+            class: "Communications".to_owned(),
+            vendor: "Future Technology Devices International, Ltd".to_owned(),
+            product: "FT232 Serial (UART) IC".to_owned(),
+            manufacturer: None,
+            class_id: Some(0x00),
+            vendor_id: 0x0403,
+            product_id: 0x6001,
+            manufacturer_id: None,
+            serial: serial.map(str::to_owned),
+            numa_node: None,
+            local_cpus: None,
+            resources: Vec::new(),
+            compatible: Vec::new(),
+            acpi_hid: None,
+            udev_properties: BTreeMap::new(),
+            udev_tags: Vec::new(),
+        }
+    }
+
+    fn usb(ports: &[u8]) -> DevicePath {
+        DevicePath::USB {
+            bus: 1,
+            ports: ports.to_vec(),
+        }
+    }
+
+    fn labels(group: &DuplicateGroup) -> Vec<&str> {
+        group.members().iter().map(|m| m.label()).collect()
+    }
+
+    #[test]
+    fn labels_by_port_path() {
+        let devices = [
+            device(usb(&[2, 10]), None),
+            device(usb(&[2, 9]), None),
+            device(usb(&[1]), None),
+        ];
+
+        let groups = DuplicateGroup::find(&devices);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            labels(&groups[0]),
+            ["usb:0403:6001@1-1", "usb:0403:6001@1-2.9", "usb:0403:6001@1-2.10"]
+        );
+        assert_eq!(groups[0].get(3).unwrap().device().path(), &usb(&[2, 10]));
+    }
+
+    #[test]
+    fn labels_do_not_depend_on_other_devices() {
+        let before = DuplicateGroup::find(&[device(usb(&[3]), None), device(usb(&[4]), None)]);
+        let after = DuplicateGroup::find(&[
+            device(usb(&[1]), None),
+            device(usb(&[4]), None),
+            device(usb(&[3]), None),
+        ]);
+
+        assert_eq!(labels(&before[0]), ["usb:0403:6001@1-3", "usb:0403:6001@1-4"]);
+        assert_eq!(
+            labels(&after[0]),
+            ["usb:0403:6001@1-1", "usb:0403:6001@1-3", "usb:0403:6001@1-4"]
+        );
+    }
+
+    #[test]
+    fn groups_by_serial() {
+        let devices = [
+            device(usb(&[1]), Some("A")),
+            device(usb(&[2]), Some("A")),
+            device(usb(&[3]), Some("B")),
+        ];
+
+        let groups = DuplicateGroup::find(&devices);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].serial(), Some("A"));
+        assert_eq!(labels(&groups[0]), ["usb:0403:6001:A@1-1", "usb:0403:6001:A@1-2"]);
+    }
+
+    #[test]
+    fn orders_pci_addresses_by_value() {
+        let pci = |bus| DevicePath::PCI {
+            domain: 0,
+            bus,
+            slot: 0,
+            function: 0,
+        };
+
+        let groups = DuplicateGroup::find(&[device(pci(0x0a), None), device(pci(0x09), None)]);
+
+        assert_eq!(
+            labels(&groups[0]),
+            ["pci:0403:6001@0000:09:00.0", "pci:0403:6001@0000:0a:00.0"]
+        );
+    }
+
+    #[test]
+    fn orders_names_naturally() {
+        let platform = |name: &str| DevicePath::Platform {
+            name: name.to_owned(),
+        };
+
+        let groups = DuplicateGroup::find(&[
+            device(platform("serial8250.10"), None),
+            device(platform("serial8250.9"), None),
+        ]);
+
+        assert_eq!(
+            labels(&groups[0]),
+            ["platform:0403:6001@serial8250.9", "platform:0403:6001@serial8250.10"]
+        );
+    }
+}
@@ -0,0 +1,703 @@
+use std::{
+    fs,
+    io,
+    path::Path,
+};
+
+use crate::error::Error;
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+const EDID_BLOCK_SIZE: usize = 128;
+
+const EXTENSION_CEA: u8 = 0x02;
+const EXTENSION_DISPLAYID: u8 = 0x70;
+
+// IEEE OUI of HDMI Licensing, LLC
+const HDMI_OUI: u32 = 0x00_0C03;
+
+/// Established timings, in the order of their bits in bytes 35 to 37.
+#[rustfmt::skip]
+const ESTABLISHED_TIMINGS: [(u16, u16, u16, bool); 17] = [
+    (720, 400, 70, false), (720, 400, 88, false), (640, 480, 60, false), (640, 480, 67, false),
+    (640, 480, 72, false), (640, 480, 75, false), (800, 600, 56, false), (800, 600, 60, false),
+    (800, 600, 72, false), (800, 600, 75, false), (832, 624, 75, false), (1024, 768, 87, true),
+    (1024, 768, 60, false), (1024, 768, 70, false), (1024, 768, 75, false), (1280, 1024, 75, false),
+    (1152, 870, 75, false),
+];
+
+/// CEA-861 Video Identification Codes with a unique resolution and refresh rate.
+#[rustfmt::skip]
+const VIDEO_CODES: [(u8, u16, u16, u16, bool); 74] = [
+    (1, 640, 480, 60, false), (2, 720, 480, 60, false), (3, 720, 480, 60, false),
+    (4, 1280, 720, 60, false), (5, 1920, 1080, 60, true), (6, 720, 480, 60, true),
+    (7, 720, 480, 60, true), (8, 720, 240, 60, false), (9, 720, 240, 60, false),
+    (10, 2880, 480, 60, true), (11, 2880, 480, 60, true), (12, 2880, 240, 60, false),
+    (13, 2880, 240, 60, false), (14, 1440, 480, 60, false), (15, 1440, 480, 60, false),
+    (16, 1920, 1080, 60, false), (17, 720, 576, 50, false), (18, 720, 576, 50, false),
+    (19, 1280, 720, 50, false), (20, 1920, 1080, 50, true), (21, 720, 576, 50, true),
+    (22, 720, 576, 50, true), (23, 720, 288, 50, false), (24, 720, 288, 50, false),
+    (25, 2880, 576, 50, true), (26, 2880, 576, 50, true), (27, 2880, 288, 50, false),
+    (28, 2880, 288, 50, false), (29, 1440, 576, 50, false), (30, 1440, 576, 50, false),
+    (31, 1920, 1080, 50, false), (32, 1920, 1080, 24, false), (33, 1920, 1080, 25, false),
+    (34, 1920, 1080, 30, false), (35, 2880, 480, 60, false), (36, 2880, 480, 60, false),
+    (37, 2880, 576, 50, false), (38, 2880, 576, 50, false), (39, 1920, 1080, 50, true),
+    (40, 1920, 1080, 100, true), (41, 1280, 720, 100, false), (42, 720, 576, 100, false),
+    (43, 720, 576, 100, false), (44, 720, 576, 100, true), (45, 720, 576, 100, true),
+    (46, 1920, 1080, 120, true), (47, 1280, 720, 120, false), (48, 720, 480, 120, false),
+    (49, 720, 480, 120, false), (50, 720, 480, 120, true), (51, 720, 480, 120, true),
+    (52, 720, 576, 200, false), (53, 720, 576, 200, false), (54, 720, 576, 200, true),
+    (55, 720, 576, 200, true), (56, 720, 480, 240, false), (57, 720, 480, 240, false),
+    (58, 720, 480, 240, true), (59, 720, 480, 240, true), (60, 1280, 720, 24, false),
+    (61, 1280, 720, 25, false), (62, 1280, 720, 30, false), (63, 1920, 1080, 120, false),
+    (64, 1920, 1080, 100, false), (93, 3840, 2160, 24, false), (94, 3840, 2160, 25, false),
+    (95, 3840, 2160, 30, false), (96, 3840, 2160, 50, false), (97, 3840, 2160, 60, false),
+    (98, 4096, 2160, 24, false), (99, 4096, 2160, 25, false), (100, 4096, 2160, 30, false),
+    (101, 4096, 2160, 50, false), (102, 4096, 2160, 60, false),
+];
+
+/// A display mode supported by a monitor.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DisplayMode {
+    /// Horizontal resolution in pixels.
+    pub width: u16,
+
+    /// Vertical resolution in pixels.
+    pub height: u16,
+
+    /// Refresh rate in Hz, rounded to the nearest integer.
+    pub refresh_rate: u16,
+
+    /// Whether the mode is interlaced.
+    pub interlaced: bool,
+}
+
+/// Information from a CEA-861 extension block.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CeaExtension {
+    pub(crate) revision: u8,
+    pub(crate) underscan: bool,
+    pub(crate) basic_audio: bool,
+    pub(crate) hdmi: bool,
+    pub(crate) video_codes: Vec<u8>,
+}
+
+impl CeaExtension {
+    /// Returns the revision of the extension block.
+    pub fn revision(&self) -> u8 {
+        self.revision
+    }
+
+    /// Returns `true` if the monitor underscans IT formats by default.
+    pub fn underscan(&self) -> bool {
+        self.underscan
+    }
+
+    /// Returns `true` if the monitor supports basic audio.
+    pub fn basic_audio(&self) -> bool {
+        self.basic_audio
+    }
+
+    /// Returns `true` if the monitor is an HDMI sink.
+    pub fn hdmi(&self) -> bool {
+        self.hdmi
+    }
+
+    /// Returns the Video Identification Codes supported by the monitor, native formats first.
+    pub fn video_codes(&self) -> &[u8] {
+        &self.video_codes
+    }
+}
+
+/// Monitor identification and capabilities, parsed from an EDID.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Edid {
+    pub(crate) manufacturer: String,
+    pub(crate) product_code: u16,
+    pub(crate) serial_number: u32,
+    pub(crate) serial_string: Option<String>,
+    pub(crate) name: Option<String>,
+
+    pub(crate) week: Option<u8>,
+    pub(crate) year: u16,
+    pub(crate) version: u8,
+    pub(crate) revision: u8,
+
+    pub(crate) width_mm: u16,
+    pub(crate) height_mm: u16,
+
+    pub(crate) preferred_mode: Option<DisplayMode>,
+    pub(crate) modes: Vec<DisplayMode>,
+
+    pub(crate) cea: Option<CeaExtension>,
+    pub(crate) displayid: bool,
+}
+
+impl Edid {
+    /// Read and parse an EDID file, such as `/sys/class/drm/card0-HDMI-A-1/edid`.
+    /// # Errors
+    /// If the file could not be read or is not a valid EDID, an error is returned.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let bytes = fs::read(path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => Error::NotFound,
+            _ => Error::CommandError,
+        })?;
+
+        Self::parse(&bytes)
+    }
+
+    /// Parse an EDID, including its CEA-861 and `DisplayID` extension blocks.
+    /// # Errors
+    /// If the base block is truncated or has an invalid header or checksum, an error is returned.
+    /// # Note
+    /// Extension blocks with an invalid checksum are ignored.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        let base = bytes.get(..EDID_BLOCK_SIZE).ok_or(Error::ParseError)?;
+
+        if base[..8] != EDID_HEADER || !checksum_valid(base) {
+            return Err(Error::ParseError);
+        }
+
+        // Manufacturer id is three 5-bit letters, where 1 is `A`
+        let id = u16::from_be_bytes([base[8], base[9]]);
+        let manufacturer = [10, 5, 0]
+            .iter()
+            .map(|shift| char::from(b'A' - 1 + ((id >> shift) & 0x1F) as u8))
+            .collect();
+
+        let mut edid = Self {
+            manufacturer,
+            product_code: u16::from_le_bytes([base[10], base[11]]),
+            serial_number: u32::from_le_bytes([base[12], base[13], base[14], base[15]]),
+            // Week 0xFF marks the year as the model year
+            week: Some(base[16]).filter(|w| (1..=54).contains(w)),
+            year: 1990 + u16::from(base[17]),
+            version: base[18],
+            revision: base[19],
+            width_mm: u16::from(base[21]) * 10,
+            height_mm: u16::from(base[22]) * 10,
+            ..Self::default()
+        };
+
+        edid.parse_established_timings(&base[35..38]);
+        edid.parse_standard_timings(&base[38..54]);
+
+        for descriptor in base[54..126].chunks_exact(18) {
+            edid.parse_descriptor(descriptor);
+        }
+
+        // Byte 126 holds the number of extension blocks that follow
+        let extensions = bytes[EDID_BLOCK_SIZE..]
+            .chunks_exact(EDID_BLOCK_SIZE)
+            .take(usize::from(base[126]))
+            .filter(|e| checksum_valid(e));
+
+        for extension in extensions {
+            match extension[0] {
+                EXTENSION_CEA => edid.parse_cea(extension),
+                EXTENSION_DISPLAYID => edid.parse_displayid(extension),
+                _ => {}
+            }
+        }
+
+        edid.modes.sort();
+        edid.modes.dedup();
+
+        Ok(edid)
+    }
+
+    fn parse_established_timings(&mut self, bytes: &[u8]) {
+        let timings = ESTABLISHED_TIMINGS.iter().enumerate();
+
+        for (i, &(width, height, refresh_rate, interlaced)) in timings {
+            if bytes[i / 8] & (0x80 >> (i % 8)) != 0 {
+                self.modes.push(DisplayMode {
+                    width,
+                    height,
+                    refresh_rate,
+                    interlaced,
+                });
+            }
+        }
+    }
+
+    fn parse_standard_timings(&mut self, bytes: &[u8]) {
+        for timing in bytes.chunks_exact(2) {
+            // Unused entries are filled with 0x01
+            if timing == [0x01, 0x01] || timing[0] == 0 {
+                continue;
+            }
+
+            let width = (u16::from(timing[0]) + 31) * 8;
+
+            let height = match timing[1] >> 6 {
+                0 if self.version == 1 && self.revision < 3 => width,
+                0 => width * 10 / 16,
+                1 => width * 3 / 4,
+                2 => width * 4 / 5,
+                _ => width * 9 / 16,
+            };
+
+            self.modes.push(DisplayMode {
+                width,
+                height,
+                refresh_rate: u16::from(timing[1] & 0x3F) + 60,
+                interlaced: false,
+            });
+        }
+    }
+
+    /// Parse an 18-byte descriptor, either a detailed timing or a display descriptor.
+    fn parse_descriptor(&mut self, bytes: &[u8]) {
+        if bytes[0] != 0 || bytes[1] != 0 {
+            if let Some(mode) = detailed_timing(bytes) {
+                // The first detailed timing is the preferred mode
+                if self.preferred_mode.is_none() {
+                    self.preferred_mode = Some(mode);
+
+                    let width_mm = u16::from(bytes[12]) | (u16::from(bytes[14] >> 4) << 8);
+                    let height_mm = u16::from(bytes[13]) | (u16::from(bytes[14] & 0x0F) << 8);
+
+                    if width_mm > 0 && height_mm > 0 {
+                        self.width_mm = width_mm;
+                        self.height_mm = height_mm;
+                    }
+                }
+
+                self.modes.push(mode);
+            }
+
+            return;
+        }
+
+        let text = || {
+            let text = &bytes[5..18];
+            let end = text.iter().position(|b| *b == b'\n').unwrap_or(text.len());
+
+            Some(String::from_utf8_lossy(&text[..end]).trim().to_owned()).filter(|t| !t.is_empty())
+        };
+
+        match bytes[3] {
+            0xFC => self.name = text(),
+            0xFF => self.serial_string = text(),
+            _ => {}
+        }
+    }
+
+    fn parse_cea(&mut self, block: &[u8]) {
+        let offset = usize::from(block[2]);
+
+        let mut cea = CeaExtension {
+            revision: block[1],
+            underscan: block[3] & 0x80 != 0,
+            basic_audio: block[3] & 0x40 != 0,
+            ..CeaExtension::default()
+        };
+
+        // Data blocks are only present from revision 3, from byte 4 up to the first detailed timing
+        let mut i = 4;
+
+        while cea.revision >= 3 && i < offset.min(block.len()) {
+            let tag = block[i] >> 5;
+            let len = usize::from(block[i] & 0x1F);
+            let Some(data) = block.get(i + 1..i + 1 + len) else {
+                break;
+            };
+
+            match tag {
+                // Video data block
+                2 => {
+                    for &svd in data {
+                        // Codes 1 to 64 use bit 7 to mark native formats
+                        let vic = if (129..=192).contains(&svd) { svd & 0x7F } else { svd };
+
+                        cea.video_codes.push(vic);
+
+                        if let Some(&(_, width, height, refresh_rate, interlaced)) =
+                            VIDEO_CODES.iter().find(|v| v.0 == vic)
+                        {
+                            self.modes.push(DisplayMode {
+                                width,
+                                height,
+                                refresh_rate,
+                                interlaced,
+                            });
+                        }
+                    }
+                }
+                // Vendor specific data block
+                3 if len >= 3 => {
+                    let oui = u32::from_le_bytes([data[0], data[1], data[2], 0]);
+                    cea.hdmi |= oui == HDMI_OUI;
+                }
+                _ => {}
+            }
+
+            i += 1 + len;
+        }
+
+        if offset >= 4 {
+            for descriptor in block[offset.min(127)..127].chunks_exact(18) {
+                if descriptor[0] == 0 && descriptor[1] == 0 {
+                    break;
+                }
+
+                self.parse_descriptor(descriptor);
+            }
+        }
+
+        self.cea = Some(cea);
+    }
+
+    fn parse_displayid(&mut self, block: &[u8]) {
+        self.displayid = true;
+
+        // The section header follows the extension tag
+        let section_len = usize::from(block[2]);
+        let end = (5 + section_len).min(block.len() - 1);
+        let mut i = 5;
+
+        while i + 3 <= end {
+            let tag = block[i];
+            let len = usize::from(block[i + 2]);
+            let Some(data) = block.get(i + 3..i + 3 + len) else {
+                break;
+            };
+
+            match tag {
+                // Product identification blocks (1.x and 2.x)
+                0x00 | 0x20 if len >= 12 => {
+                    let name_len = usize::from(data[11]);
+
+                    if self.name.is_none() {
+                        self.name = data
+                            .get(12..12 + name_len)
+                            .map(|n| String::from_utf8_lossy(n).trim().to_owned())
+                            .filter(|n| !n.is_empty());
+                    }
+                }
+                // Type I (1.x) and type VII (2.x) detailed timings
+                0x03 | 0x22 => {
+                    let unit_khz = if tag == 0x03 { 10 } else { 1 };
+
+                    for timing in data.chunks_exact(20) {
+                        self.modes.extend(displayid_timing(timing, unit_khz));
+                    }
+                }
+                _ => {}
+            }
+
+            i += 3 + len;
+        }
+    }
+
+    /// Returns the three-letter PNP id of the manufacturer, such as `DEL`.
+    pub fn manufacturer(&self) -> &str {
+        &self.manufacturer
+    }
+
+    /// Returns the manufacturer's product code.
+    pub fn product_code(&self) -> u16 {
+        self.product_code
+    }
+
+    /// Returns the numeric serial number, or `0` if not set.
+    pub fn serial_number(&self) -> u32 {
+        self.serial_number
+    }
+
+    /// Returns the serial number string, if set.
+    pub fn serial_string(&self) -> Option<&str> {
+        self.serial_string.as_deref()
+    }
+
+    /// Returns the monitor name, if set.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the week of manufacture, if known.
+    pub fn week(&self) -> Option<u8> {
+        self.week
+    }
+
+    /// Returns the year of manufacture, or the model year.
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Returns the EDID version and revision, such as `(1, 4)`.
+    pub fn version(&self) -> (u8, u8) {
+        (self.version, self.revision)
+    }
+
+    /// Returns the physical width of the display area in millimeters, or `0` if unknown.
+    pub fn width_mm(&self) -> u16 {
+        self.width_mm
+    }
+
+    /// Returns the physical height of the display area in millimeters, or `0` if unknown.
+    pub fn height_mm(&self) -> u16 {
+        self.height_mm
+    }
+
+    /// Returns the preferred mode of the monitor, if known.
+    pub fn preferred_mode(&self) -> Option<DisplayMode> {
+        self.preferred_mode
+    }
+
+    /// Returns all modes supported by the monitor, sorted.
+    pub fn modes(&self) -> &[DisplayMode] {
+        &self.modes
+    }
+
+    /// Returns the CEA-861 extension, if present.
+    pub fn cea(&self) -> Option<&CeaExtension> {
+        self.cea.as_ref()
+    }
+
+    /// Returns `true` if a `DisplayID` extension is present.
+    pub fn has_displayid(&self) -> bool {
+        self.displayid
+    }
+}
+
+/// Returns `true` if the bytes of a block, including its checksum byte, add up to 0 modulo 256.
+fn checksum_valid(block: &[u8]) -> bool {
+    block.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) == 0
+}
+
+fn refresh_rate(pixel_clock_hz: u64, h_total: u64, v_total: u64) -> Option<u16> {
+    let total = h_total * v_total;
+
+    if total == 0 {
+        return None;
+    }
+
+    u16::try_from((pixel_clock_hz + total / 2) / total).ok()
+}
+
+/// Parse an 18-byte detailed timing descriptor.
+fn detailed_timing(bytes: &[u8]) -> Option<DisplayMode> {
+    let pixel_clock = u64::from(u16::from_le_bytes([bytes[0], bytes[1]])) * 10_000;
+
+    let width = u16::from(bytes[2]) | (u16::from(bytes[4] >> 4) << 8);
+    let h_blank = u16::from(bytes[3]) | (u16::from(bytes[4] & 0x0F) << 8);
+    let height = u16::from(bytes[5]) | (u16::from(bytes[7] >> 4) << 8);
+    let v_blank = u16::from(bytes[6]) | (u16::from(bytes[7] & 0x0F) << 8);
+    let interlaced = bytes[17] & 0x80 != 0;
+
+    let refresh_rate = refresh_rate(
+        pixel_clock,
+        u64::from(width + h_blank),
+        u64::from(height + v_blank),
+    )?;
+
+    // Interlaced timings describe a single field
+    Some(DisplayMode {
+        width,
+        height: if interlaced { height * 2 } else { height },
+        refresh_rate,
+        interlaced,
+    })
+}
+
+/// Parse a 20-byte `DisplayID` detailed timing, where values are stored minus one.
+fn displayid_timing(bytes: &[u8], unit_khz: u64) -> Option<DisplayMode> {
+    let pixel_clock = u64::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]));
+    let pixel_clock = (pixel_clock + 1) * unit_khz * 1000;
+
+    let field = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]).checked_add(1);
+
+    let width = field(4)?;
+    let h_blank = field(6)?;
+    let height = field(12)?;
+    let v_blank = field(14)?;
+    let interlaced = bytes[3] & 0x10 != 0;
+
+    Some(DisplayMode {
+        width,
+        height,
+        refresh_rate: refresh_rate(
+            pixel_clock,
+            u64::from(width) + u64::from(h_blank),
+            u64::from(height) + u64::from(v_blank),
+        )?,
+        interlaced,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode(width: u16, height: u16, refresh_rate: u16) -> DisplayMode {
+        DisplayMode {
+            width,
+            height,
+            refresh_rate,
+            interlaced: false,
+        }
+    }
+
+    /// Sets the last byte of a block so that its bytes add up to 0 modulo 256.
+    fn seal(block: &mut [u8]) {
+        let sum = block[..EDID_BLOCK_SIZE - 1].iter().fold(0u8, |a, b| a.wrapping_add(*b));
+        block[EDID_BLOCK_SIZE - 1] = sum.wrapping_neg();
+    }
+
+    /// A 24" 1920x1200 EDID 1.4 base block, laid out like a Dell U2415.
+    fn base_block(extensions: u8) -> Vec<u8> {
+        let mut block = vec![0; EDID_BLOCK_SIZE];
+
+        block[..8].copy_from_slice(&EDID_HEADER);
+        block[8..20].copy_from_slice(&[
+            0x10, 0xAC, // Manufacturer (DEL)
+            0xC4, 0xA0, // Product code (0xA0C4)
+            0x4C, 0x4A, 0x33, 0x30, // Serial number
+            12,   29, // Week 12 of 2019
+            1,    4, // Version 1.4
+        ]);
+        block[20..23].copy_from_slice(&[0xA5, 53, 30]);
+        // 640x480@60, 800x600@60 and 1024x768@60
+        block[35..38].copy_from_slice(&[0x21, 0x08, 0x00]);
+        // 1920x1080@60 and 1280x1024@60, then unused entries
+        block[38..54].copy_from_slice(&[
+            0xD1, 0xC0, 0x81, 0x80, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+            0x01, 0x01,
+        ]);
+        // 1920x1200@60 reduced blanking, 518x324 mm
+        block[54..72].copy_from_slice(&[
+            0x28, 0x3C, 0x80, 0xA0, 0x70, 0xB0, 0x23, 0x40, 0x30, 0x20, 0x36, 0x00, 0x06, 0x44,
+            0x21, 0x00, 0x00, 0x1A,
+        ]);
+        block[72..77].copy_from_slice(&[0x00, 0x00, 0x00, 0xFF, 0x00]);
+        block[77..90].copy_from_slice(b"CFV9N99T0L0L\n");
+        block[90..95].copy_from_slice(&[0x00, 0x00, 0x00, 0xFC, 0x00]);
+        block[95..108].copy_from_slice(b"DELL U2415\n  ");
+        // Range limits, which are not parsed
+        block[108..126].copy_from_slice(&[
+            0x00, 0x00, 0x00, 0xFD, 0x00, 0x38, 0x4C, 0x1E, 0x51, 0x11, 0x00, 0x0A, 0x20, 0x20,
+            0x20, 0x20, 0x20, 0x20,
+        ]);
+        block[126] = extensions;
+
+        seal(&mut block);
+        block
+    }
+
+    /// A CEA-861 revision 3 block of an HDMI sink with basic audio.
+    fn cea_block() -> Vec<u8> {
+        let mut block = vec![0; EDID_BLOCK_SIZE];
+
+        block[..14].copy_from_slice(&[
+            EXTENSION_CEA,
+            0x03, // Revision 3
+            14,   // Detailed timings start at byte 14
+            0x70, // Basic audio, no underscan
+            0x43, 0x90, 0x04, 0x1F, // Video data block: 1080p60 (native), 720p60, 1080p50
+            0x65, 0x03, 0x0C, 0x00, 0x10, 0x00, // HDMI vendor specific data block
+        ]);
+        // 1280x720@60
+        block[14..32].copy_from_slice(&[
+            0x01, 0x1D, 0x00, 0x72, 0x51, 0xD0, 0x1E, 0x20, 0x6E, 0x28, 0x55, 0x00, 0x20, 0xC2,
+            0x31, 0x00, 0x00, 0x1E,
+        ]);
+
+        seal(&mut block);
+        block
+    }
+
+    #[test]
+    fn parses_base_block() {
+        let edid = Edid::parse(&base_block(0)).unwrap();
+
+        assert_eq!(edid.manufacturer(), "DEL");
+        assert_eq!(edid.product_code(), 0xA0C4);
+        assert_eq!(edid.serial_number(), 0x3033_4A4C);
+        assert_eq!(edid.serial_string(), Some("CFV9N99T0L0L"));
+        assert_eq!(edid.name(), Some("DELL U2415"));
+        assert_eq!(edid.week(), Some(12));
+        assert_eq!(edid.year(), 2019);
+        assert_eq!(edid.version(), (1, 4));
+        assert_eq!((edid.width_mm(), edid.height_mm()), (518, 324));
+        assert_eq!(edid.preferred_mode(), Some(mode(1920, 1200, 60)));
+        assert_eq!(
+            edid.modes(),
+            [
+                mode(640, 480, 60),
+                mode(800, 600, 60),
+                mode(1024, 768, 60),
+                mode(1280, 1024, 60),
+                mode(1920, 1080, 60),
+                mode(1920, 1200, 60),
+            ]
+        );
+        assert!(edid.cea().is_none());
+        assert!(!edid.has_displayid());
+    }
+
+    #[test]
+    fn parses_cea_extension() {
+        let bytes = [base_block(1), cea_block()].concat();
+        let edid = Edid::parse(&bytes).unwrap();
+
+        let cea = edid.cea().unwrap();
+        assert_eq!(cea.revision(), 3);
+        assert!(!cea.underscan());
+        assert!(cea.basic_audio());
+        assert!(cea.hdmi());
+        assert_eq!(cea.video_codes(), [16, 4, 31]);
+
+        assert_eq!(edid.preferred_mode(), Some(mode(1920, 1200, 60)));
+        assert_eq!(
+            edid.modes(),
+            [
+                mode(640, 480, 60),
+                mode(800, 600, 60),
+                mode(1024, 768, 60),
+                mode(1280, 720, 60),
+                mode(1280, 1024, 60),
+                mode(1920, 1080, 50),
+                mode(1920, 1080, 60),
+                mode(1920, 1200, 60),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_base_block() {
+        let mut bytes = base_block(0);
+
+        assert!(matches!(Edid::parse(&bytes[..127]), Err(Error::ParseError)));
+
+        bytes[20] ^= 0x01;
+        assert!(matches!(Edid::parse(&bytes), Err(Error::ParseError)));
+
+        bytes[0] = 0xFF;
+        seal(&mut bytes);
+        assert!(matches!(Edid::parse(&bytes), Err(Error::ParseError)));
+    }
+
+    #[test]
+    fn ignores_invalid_extensions() {
+        let mut cea = cea_block();
+        cea[3] ^= 0x40;
+
+        let bytes = [base_block(1), cea].concat();
+        let edid = Edid::parse(&bytes).unwrap();
+
+        assert!(edid.cea().is_none());
+        assert_eq!(edid.modes().len(), 6);
+
+        // Blocks beyond the extension count in the base block are not part of the EDID
+        let bytes = [base_block(0), cea_block()].concat();
+
+        assert!(Edid::parse(&bytes).unwrap().cea().is_none());
+    }
+}
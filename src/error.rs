@@ -14,4 +14,160 @@ pub enum Error {
     #[error("could not parse device information")]
     /// The result returned properly but could not be parsed.
     ParseError,
+
+    #[error("could not encode device information")]
+    /// The device information could not be encoded.
+    EncodeError,
+
+    #[error("data was written by a newer, incompatible version")]
+    /// The data requires a newer version of this crate to be read.
+    UnsupportedVersion,
+
+    #[error("device or attribute not found")]
+    /// The requested device or attribute does not exist.
+    NotFound,
+
+    #[error("directory is not empty")]
+    /// The directory to write to already has entries.
+    NotEmpty,
+}
+
+/// An error that could be returned when parsing a [`DevicePath`](crate::DevicePath).
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum ParsePathError {
+    #[error("missing bus, expected `<bus>:<address>`")]
+    /// The path has no bus prefix.
+    MissingBus,
+
+    #[error("unknown bus `{0}`")]
+    /// The bus prefix is not recognized.
+    UnknownBus(String),
+
+    #[error("invalid PCI address `{0}`, expected `[DDDD:]BB:SS.F`")]
+    /// The PCI address is malformed or out of range.
+    InvalidPci(String),
+
+    #[error("invalid USB port path `{0}`, expected `B-P[.P...]` or `usbB`")]
+    /// The USB port path is malformed or out of range.
+    InvalidUsb(String),
+
+    #[error("`{0}` is a USB bus and device number, which change when the device is reconnected")]
+    /// The USB address is a bus and device number such as `001:002`, rather than a port path.
+    ///
+    /// Use [`DevicePath::from_lsusb`](crate::DevicePath::from_lsusb) with `Bus 001 Device 002`
+    /// to find the port a connected device is on.
+    UsbAddress(String),
+
+    #[error("missing device name")]
+    /// The device name is empty.
+    EmptyName,
+}
+
+/// An error that could be returned when parsing a [`Selector`](crate::Selector).
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+#[error("{kind} at column {column}")]
+pub struct ParseSelectorError {
+    pub(crate) column: usize,
+    pub(crate) kind: SelectorErrorKind,
+}
+
+impl ParseSelectorError {
+    /// Returns the column the error occurred at, starting from `1`.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the kind of error.
+    pub fn kind(&self) -> &SelectorErrorKind {
+        &self.kind
+    }
+}
+
+/// The kind of a [`ParseSelectorError`].
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum SelectorErrorKind {
+    #[error("empty selector")]
+    /// The selector contains no terms.
+    Empty,
+
+    #[error("unexpected end of selector")]
+    /// The selector ended in the middle of an expression.
+    UnexpectedEnd,
+
+    #[error("unexpected `{0}`")]
+    /// A token appeared where it is not allowed.
+    UnexpectedToken(String),
+
+    #[error("unknown bus `{0}`, expected a bus name or `key=value`")]
+    /// A bare word is not a bus name.
+    UnknownBus(String),
+
+    #[error("unknown key `{0}`")]
+    /// The key of a term is not recognized.
+    UnknownKey(String),
+
+    #[error("missing value for `{0}`")]
+    /// A key has no operator or value.
+    MissingValue(String),
+
+    #[error("invalid value `{value}` for `{key}`, expected {expected}")]
+    /// The value of a term is not valid for its key.
+    InvalidValue {
+        /// The key of the term.
+        key: String,
+
+        /// The invalid value.
+        value: String,
+
+        /// A description of the expected value.
+        expected: &'static str,
+    },
+
+    #[error("unterminated string")]
+    /// A quoted value has no closing quote.
+    UnterminatedString,
+
+    #[error("unclosed parenthesis")]
+    /// An opening parenthesis has no matching closing parenthesis.
+    UnclosedParen,
+}
+
+/// An error that could be returned when parsing [`UdevRules`](crate::UdevRules).
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+#[error("{kind} on line {line}")]
+pub struct ParseRulesError {
+    pub(crate) line: usize,
+    pub(crate) kind: RuleErrorKind,
+}
+
+impl ParseRulesError {
+    /// Returns the line the rule with the error starts on, starting from `1`.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the kind of error.
+    pub fn kind(&self) -> &RuleErrorKind {
+        &self.kind
+    }
+}
+
+/// The kind of a [`ParseRulesError`].
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum RuleErrorKind {
+    #[error("malformed rule at `{0}`")]
+    /// The rule is not a list of `KEY op "value"` pairs.
+    Malformed(String),
+
+    #[error("unsupported key `{0}`")]
+    /// The key is not supported with this operator, or not supported at all.
+    UnsupportedKey(String),
+
+    #[error("value is not quoted")]
+    /// A value is not enclosed in double quotes.
+    UnquotedValue,
+
+    #[error("unterminated value")]
+    /// A value has no closing quote.
+    UnterminatedValue,
 }
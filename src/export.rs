@@ -0,0 +1,604 @@
+//! Exchange formats for device lists.
+//!
+//! JSON and YAML documents share one schema:
+//!
+//! ```json
+//! {
+//!   "schema": 1,
+//!   "devices": [
+//!     {
+//!       "path": "pci:0000:00:1f.3",
+//!       "bus": "pci",
+//!       "class": "Audio device",
+//!       "class_id": "0403",
+//!       "vendor": "Intel Corporation",
+//!       "vendor_id": "8086",
+//!       "product": "Cannon Lake PCH cAVS",
+//!       "product_id": "a348",
+//!       "manufacturer": null,
+//!       "manufacturer_id": null,
+//!       "serial": null,
+//!       "numa_node": null,
+//!       "local_cpus": [0, 1, 2, 3],
+//!       "resources": [
+//!         { "index": 0, "start": "0xa1210000", "size": "0x4000", "flags": "0x140204" }
+//!       ],
+//!       "compatible": [],
+//!       "acpi_hid": null,
+//!       "udev_properties": { "ID_PATH": "pci-0000:00:1f.3" },
+//!       "udev_tags": []
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! - `path` is the canonical string form of [`DevicePath`](crate::DevicePath).
+//! - `bus` is informational and ignored when importing.
+//! - IDs are four lowercase hex digits without a prefix, as printed by `lspci` and `lsusb`.
+//! - Resource addresses, sizes and flags are lowercase hex with a `0x` prefix.
+//! - Fields other than `path`, `class`, `vendor`, `product`, `vendor_id` and `product_id`
+//!   may be omitted.
+//!
+//! CSV files have one row per device with the same columns in the same order.
+//! Lists are joined with `;`, resources are written as `index:start:size:flags`,
+//! and empty cells stand for missing values, so an empty `local_cpus` list is read back as missing.
+//! udev properties and tags are left out of CSV files.
+
+use std::collections::BTreeMap;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    DeviceInfo,
+    Error,
+    PciResource,
+};
+
+/// The version of the exchange schema written by this crate.
+///
+/// Documents with a newer version are rejected when importing.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[cfg(any(feature = "json", feature = "yaml"))]
+#[derive(Deserialize, Serialize)]
+struct Document {
+    schema: u32,
+    devices: Vec<Record>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Record {
+    path: String,
+    #[serde(default)]
+    bus: String,
+
+    class: String,
+    #[serde(default)]
+    class_id: Option<String>,
+    vendor: String,
+    vendor_id: String,
+    product: String,
+    product_id: String,
+    #[serde(default)]
+    manufacturer: Option<String>,
+    #[serde(default)]
+    manufacturer_id: Option<String>,
+    #[serde(default)]
+    serial: Option<String>,
+
+    #[serde(default)]
+    numa_node: Option<u16>,
+    #[serde(default)]
+    local_cpus: Option<Vec<usize>>,
+
+    #[serde(default)]
+    resources: Vec<ResourceRecord>,
+
+    #[serde(default)]
+    compatible: Vec<String>,
+    #[serde(default)]
+    acpi_hid: Option<String>,
+
+    #[serde(default)]
+    udev_properties: BTreeMap<String, String>,
+    #[serde(default)]
+    udev_tags: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ResourceRecord {
+    index: u8,
+    start: String,
+    size: String,
+    flags: String,
+}
+
+fn format_id(id: u16) -> String {
+    format!("{id:04x}")
+}
+
+fn parse_id(s: &str) -> Result<u16, Error> {
+    let s = s.trim();
+    let s = s.strip_prefix("0x").unwrap_or(s);
+
+    u16::from_str_radix(s, 16).map_err(|_| Error::ParseError)
+}
+
+fn format_hex(value: u64) -> String {
+    format!("{value:#x}")
+}
+
+fn parse_hex(s: &str) -> Result<u64, Error> {
+    let s = s.trim();
+    let s = s.strip_prefix("0x").unwrap_or(s);
+
+    u64::from_str_radix(s, 16).map_err(|_| Error::ParseError)
+}
+
+impl From<&DeviceInfo> for Record {
+    fn from(device: &DeviceInfo) -> Self {
+        Self {
+            path: device.path.to_string(),
+            bus: device.path.bus().to_owned(),
+
+            class: device.class.clone(),
+            class_id: device.class_id.map(format_id),
+            vendor: device.vendor.clone(),
+            vendor_id: format_id(device.vendor_id),
+            product: device.product.clone(),
+            product_id: format_id(device.product_id),
+            manufacturer: device.manufacturer.clone(),
+            manufacturer_id: device.manufacturer_id.map(format_id),
+            serial: device.serial.clone(),
+
+            numa_node: device.numa_node,
+            local_cpus: device.local_cpus.clone(),
+
+            resources: device
+                .resources
+                .iter()
+                .map(|r| ResourceRecord {
+                    index: r.index,
+                    start: format_hex(r.start),
+                    size: format_hex(r.size),
+                    flags: format_hex(r.flags),
+                })
+                .collect(),
+
+            compatible: device.compatible.clone(),
+            acpi_hid: device.acpi_hid.clone(),
+
+            udev_properties: device.udev_properties.clone(),
+            udev_tags: device.udev_tags.clone(),
+        }
+    }
+}
+
+impl TryFrom<Record> for DeviceInfo {
+    type Error = Error;
+
+    fn try_from(record: Record) -> Result<Self, Error> {
+        let resources = record
+            .resources
+            .iter()
+            .map(|r| {
+                Ok(PciResource {
+                    index: r.index,
+                    start: parse_hex(&r.start)?,
+                    size: parse_hex(&r.size)?,
+                    flags: parse_hex(&r.flags)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            path: record.path.parse().map_err(|_| Error::ParseError)?,
+
+            class: record.class,
+            vendor: record.vendor,
+            product: record.product,
+            manufacturer: record.manufacturer,
+
+            class_id: record.class_id.as_deref().map(parse_id).transpose()?,
+            vendor_id: parse_id(&record.vendor_id)?,
+            product_id: parse_id(&record.product_id)?,
+            manufacturer_id: record.manufacturer_id.as_deref().map(parse_id).transpose()?,
+
+            serial: record.serial,
+
+            numa_node: record.numa_node,
+            local_cpus: record.local_cpus,
+
+            resources,
+
+            compatible: record.compatible,
+            acpi_hid: record.acpi_hid,
+
+            udev_properties: record.udev_properties,
+            udev_tags: record.udev_tags,
+        })
+    }
+}
+
+#[cfg(any(feature = "json", feature = "yaml"))]
+fn to_document(devices: &[DeviceInfo]) -> Document {
+    Document {
+        schema: SCHEMA_VERSION,
+        devices: devices.iter().map(Record::from).collect(),
+    }
+}
+
+#[cfg(any(feature = "json", feature = "yaml"))]
+fn from_document(document: Document) -> Result<Vec<DeviceInfo>, Error> {
+    if document.schema > SCHEMA_VERSION {
+        return Err(Error::UnsupportedVersion);
+    }
+
+    document.devices.into_iter().map(DeviceInfo::try_from).collect()
+}
+
+/// Encodes a list of devices as a JSON document.
+/// # Errors
+/// If the document could not be encoded, an error is returned.
+#[cfg(feature = "json")]
+pub fn to_json(devices: &[DeviceInfo]) -> Result<String, Error> {
+    serde_json::to_string_pretty(&to_document(devices)).map_err(|_| Error::EncodeError)
+}
+
+/// Decodes a list of devices from a JSON document.
+/// # Errors
+/// If the document is malformed or was written with a newer schema, an error is returned.
+#[cfg(feature = "json")]
+pub fn from_json(json: &str) -> Result<Vec<DeviceInfo>, Error> {
+    from_document(serde_json::from_str(json).map_err(|_| Error::ParseError)?)
+}
+
+/// Encodes a list of devices as a YAML document.
+/// # Errors
+/// If the document could not be encoded, an error is returned.
+#[cfg(feature = "yaml")]
+pub fn to_yaml(devices: &[DeviceInfo]) -> Result<String, Error> {
+    serde_yaml::to_string(&to_document(devices)).map_err(|_| Error::EncodeError)
+}
+
+/// Decodes a list of devices from a YAML document.
+/// # Errors
+/// If the document is malformed or was written with a newer schema, an error is returned.
+#[cfg(feature = "yaml")]
+pub fn from_yaml(yaml: &str) -> Result<Vec<DeviceInfo>, Error> {
+    from_document(serde_yaml::from_str(yaml).map_err(|_| Error::ParseError)?)
+}
+
+#[cfg(feature = "csv")]
+#[derive(Deserialize, Serialize)]
+struct CsvRecord {
+    path: String,
+    bus: String,
+    class: String,
+    class_id: String,
+    vendor: String,
+    vendor_id: String,
+    product: String,
+    product_id: String,
+    manufacturer: String,
+    manufacturer_id: String,
+    serial: String,
+    numa_node: String,
+    local_cpus: String,
+    resources: String,
+    compatible: String,
+    acpi_hid: String,
+}
+
+#[cfg(feature = "csv")]
+fn join<T: ToString>(values: &[T]) -> String {
+    values.iter().map(T::to_string).collect::<Vec<_>>().join(";")
+}
+
+#[cfg(feature = "csv")]
+fn split(cell: &str) -> impl Iterator<Item = &str> {
+    cell.split(';').filter(|s| !s.is_empty())
+}
+
+#[cfg(feature = "csv")]
+fn non_empty(cell: String) -> Option<String> {
+    if cell.is_empty() {
+        None
+    } else {
+        Some(cell)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<Record> for CsvRecord {
+    fn from(record: Record) -> Self {
+        let resources: Vec<String> = record
+            .resources
+            .iter()
+            .map(|r| format!("{}:{}:{}:{}", r.index, r.start, r.size, r.flags))
+            .collect();
+
+        Self {
+            path: record.path,
+            bus: record.bus,
+            class: record.class,
+            class_id: record.class_id.unwrap_or_default(),
+            vendor: record.vendor,
+            vendor_id: record.vendor_id,
+            product: record.product,
+            product_id: record.product_id,
+            manufacturer: record.manufacturer.unwrap_or_default(),
+            manufacturer_id: record.manufacturer_id.unwrap_or_default(),
+            serial: record.serial.unwrap_or_default(),
+            numa_node: record.numa_node.map(|n| n.to_string()).unwrap_or_default(),
+            local_cpus: record.local_cpus.as_deref().map(join).unwrap_or_default(),
+            resources: join(&resources),
+            compatible: join(&record.compatible),
+            acpi_hid: record.acpi_hid.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl TryFrom<CsvRecord> for Record {
+    type Error = Error;
+
+    fn try_from(row: CsvRecord) -> Result<Self, Error> {
+        let resources = split(&row.resources)
+            .map(|r| {
+                let mut parts = r.split(':');
+                let mut next = || parts.next().map(str::to_owned).ok_or(Error::ParseError);
+
+                Ok(ResourceRecord {
+                    index: next()?.parse().map_err(|_| Error::ParseError)?,
+                    start: next()?,
+                    size: next()?,
+                    flags: next()?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let local_cpus = if row.local_cpus.is_empty() {
+            None
+        } else {
+            Some(
+                split(&row.local_cpus)
+                    .map(|c| c.parse().map_err(|_| Error::ParseError))
+                    .collect::<Result<_, _>>()?,
+            )
+        };
+
+        Ok(Self {
+            path: row.path,
+            bus: row.bus,
+            class: row.class,
+            class_id: non_empty(row.class_id),
+            vendor: row.vendor,
+            vendor_id: row.vendor_id,
+            product: row.product,
+            product_id: row.product_id,
+            manufacturer: non_empty(row.manufacturer),
+            manufacturer_id: non_empty(row.manufacturer_id),
+            serial: non_empty(row.serial),
+            numa_node: non_empty(row.numa_node)
+                .map(|n| n.parse().map_err(|_| Error::ParseError))
+                .transpose()?,
+            local_cpus,
+            resources,
+            compatible: split(&row.compatible).map(str::to_owned).collect(),
+            acpi_hid: non_empty(row.acpi_hid),
+            udev_properties: BTreeMap::new(),
+            udev_tags: Vec::new(),
+        })
+    }
+}
+
+/// Encodes a list of devices as CSV with a header row.
+/// # Errors
+/// If the table could not be encoded, an error is returned.
+#[cfg(feature = "csv")]
+pub fn to_csv(devices: &[DeviceInfo]) -> Result<String, Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    for device in devices {
+        writer
+            .serialize(CsvRecord::from(Record::from(device)))
+            .map_err(|_| Error::EncodeError)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|_| Error::EncodeError)?;
+
+    String::from_utf8(bytes).map_err(|_| Error::EncodeError)
+}
+
+/// Decodes a list of devices from CSV with a header row.
+/// # Note
+/// Empty cells cannot be told apart from empty lists, so devices with an empty `local_cpus` list
+/// are decoded without local CPUs.
+/// # Errors
+/// If the table is malformed, an error is returned.
+#[cfg(feature = "csv")]
+pub fn from_csv(csv: &str) -> Result<Vec<DeviceInfo>, Error> {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+
+    reader
+        .deserialize::<CsvRecord>()
+        .map(|row| {
+            let row = row.map_err(|_| Error::ParseError)?;
+
+            DeviceInfo::try_from(Record::try_from(row)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DevicePath;
+
+    fn devices() -> Vec<DeviceInfo> {
+        vec![
+            DeviceInfo {
+                path: DevicePath::PCI {
+                    domain: 0x0001,
+                    bus: 0x3a,
+                    slot: 0x00,
+                    function: 0x0,
+                },
+                class: "Ethernet controller".to_owned(),
+                vendor: "Intel Corporation".to_owned(),
+                product: "Ethernet Controller E810-C for QSFP".to_owned(),
+                manufacturer: None,
+                class_id: Some(0x0200),
+                vendor_id: 0x8086,
+                product_id: 0x1592,
+                manufacturer_id: None,
+                serial: None,
+                numa_node: Some(1),
+                local_cpus: Some(vec![16, 17, 18, 19]),
+                resources: vec![PciResource {
+                    index: 0,
+                    start: 0x2_0000_0000,
+                    size: 0x200_0000,
+                    flags: 0x0014_220c,
+                }],
+                compatible: Vec::new(),
+                acpi_hid: None,
+                udev_properties: BTreeMap::from([(
+                    "ID_PATH".to_owned(),
+                    "pci-0001:3a:00.0".to_owned(),
+                )]),
+                udev_tags: vec!["systemd".to_owned()],
+            },
+            DeviceInfo {
+                path: DevicePath::USB {
+                    bus: 1,
+                    ports: vec![2, 3],
+                },
+                class: "Communications".to_owned(),
+                vendor: "Future Technology Devices International, Ltd".to_owned(),
+                product: "FT232 Serial (UART) IC".to_owned(),
+                manufacturer: Some("FTDI".to_owned()),
+                class_id: Some(0x00),
+                vendor_id: 0x0403,
+                product_id: 0x6001,
+                manufacturer_id: Some(1),
+                serial: Some("A10K3XYZ".to_owned()),
+                numa_node: None,
+                local_cpus: None,
+                resources: Vec::new(),
+                compatible: Vec::new(),
+                acpi_hid: None,
+                udev_properties: BTreeMap::new(),
+                udev_tags: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn records_use_canonical_paths() {
+        let paths: Vec<String> = devices().iter().map(|d| Record::from(d).path).collect();
+
+        assert_eq!(paths, ["pci:0001:3a:00.0", "usb:1-2.3"]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trips() {
+        let json = to_json(&devices()).unwrap();
+
+        assert!(json.contains("\"path\": \"pci:0001:3a:00.0\""));
+        assert!(json.contains("\"path\": \"usb:1-2.3\""));
+        assert_eq!(from_json(&json).unwrap(), devices());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_rejects_usb_device_numbers() {
+        let json = r#"{
+            "schema": 1,
+            "devices": [{
+                "path": "usb:001:002",
+                "class": "Hub",
+                "vendor": "Linux Foundation",
+                "vendor_id": "1d6b",
+                "product": "2.0 root hub",
+                "product_id": "0002"
+            }]
+        }"#;
+
+        assert!(from_json(json).is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_accepts_paths_without_domain() {
+        let json = r#"{
+            "schema": 1,
+            "devices": [{
+                "path": "pci:00:1f.3",
+                "class": "Audio device",
+                "vendor": "Intel Corporation",
+                "vendor_id": "8086",
+                "product": "Cannon Lake PCH cAVS",
+                "product_id": "a348"
+            }]
+        }"#;
+
+        let devices = from_json(json).unwrap();
+
+        assert_eq!(devices[0].path().to_string(), "pci:0000:00:1f.3");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_rejects_newer_schemas() {
+        let json = format!(r#"{{ "schema": {}, "devices": [] }}"#, SCHEMA_VERSION + 1);
+
+        assert!(matches!(from_json(&json), Err(Error::UnsupportedVersion)));
+        assert!(matches!(from_json(r#"{ "devices": [] }"#), Err(Error::ParseError)));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_round_trips() {
+        let yaml = to_yaml(&devices()).unwrap();
+
+        assert!(yaml.contains("path: pci:0001:3a:00.0"));
+        assert!(yaml.contains("path: usb:1-2.3"));
+        assert_eq!(from_yaml(&yaml).unwrap(), devices());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_round_trips_without_udev_data() {
+        let csv = to_csv(&devices()).unwrap();
+
+        let expected: Vec<DeviceInfo> = devices()
+            .into_iter()
+            .map(|mut d| {
+                d.udev_properties.clear();
+                d.udev_tags.clear();
+                d
+            })
+            .collect();
+
+        assert!(csv.contains("pci:0001:3a:00.0,pci,"));
+        assert!(csv.contains("usb:1-2.3,usb,"));
+        assert_eq!(from_csv(&csv).unwrap(), expected);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_reads_empty_local_cpus_as_missing() {
+        let mut device = devices().remove(1);
+        device.local_cpus = Some(Vec::new());
+
+        let devices = from_csv(&to_csv(&[device]).unwrap()).unwrap();
+
+        assert_eq!(devices[0].local_cpus, None);
+    }
+}
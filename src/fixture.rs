@@ -0,0 +1,129 @@
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use cfg_if::cfg_if;
+
+use crate::{
+    DeviceInfo,
+    Error,
+};
+
+/// The fixture format version written by this crate.
+const FIXTURE_VERSION: u16 = 1;
+
+/// The name of the file describing a fixture.
+const MANIFEST: &str = "fixture";
+
+/// A recording of what the Linux backend reads from a system, for reproducing issues without the hardware.
+///
+/// A fixture is a self-contained directory holding:
+/// - `commands/`: the output of `lspci` and `lsusb`
+/// - `sys/`: the sysfs device directories, class directories, attributes and links read by the Linux backend
+/// - `sys/kernel/debug/bluetooth/`: the Bluetooth controller details read through HCI sockets
+/// - `run/udev/data/`: the udev database records of those devices
+/// - `proc/`: the host name, kernel release, input devices and ALSA sound cards
+/// - `fixture`: the format version and the version of this crate that recorded it
+///
+/// Directories can be archived, attached to bug reports and replayed on another machine.
+/// # Note
+/// While replaying, every function of the Linux backend reads from the fixture, and Bluetooth
+/// controllers and network interfaces are not queried through sockets. Setters such as
+/// [`Devices::set_power_control`](crate::Devices::set_power_control) write into the fixture.
+/// Attributes that map device memory, such as `resource0` and `rom`, are not recorded.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Fixture {
+    pub(crate) dir: PathBuf,
+}
+
+impl Fixture {
+    /// Records a fixture of the current system into a new or empty directory.
+    /// # Errors
+    /// If the platform is unsupported or the fixture could not be written, an error is returned.
+    /// If the directory is not empty, [`Error::NotEmpty`] is returned.
+    pub fn record(dir: &Path) -> Result<Self, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                let has_entries = fs::read_dir(dir).is_ok_and(|mut d| d.next().is_some());
+
+                if has_entries {
+                    return Err(Error::NotEmpty);
+                }
+
+                crate::linux::record_fixture(dir)?;
+
+                let manifest = format!(
+                    "version={FIXTURE_VERSION}\ncrate_version={}\n",
+                    env!("CARGO_PKG_VERSION")
+                );
+
+                fs::write(dir.join(MANIFEST), manifest).map_err(|_| Error::CommandError)?;
+
+                Ok(Self {
+                    dir: dir.to_owned(),
+                })
+            } else {
+                let _ = dir;
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Opens a recorded fixture.
+    /// # Errors
+    /// If the directory is not a fixture, or it was recorded in a newer format, an error is returned.
+    pub fn open(dir: &Path) -> Result<Self, Error> {
+        let manifest = fs::read_to_string(dir.join(MANIFEST)).map_err(|_| Error::NotFound)?;
+
+        let version = manifest
+            .lines()
+            .find_map(|l| l.strip_prefix("version="))
+            .and_then(|v| v.trim().parse::<u16>().ok())
+            .ok_or(Error::ParseError)?;
+
+        if version > FIXTURE_VERSION {
+            return Err(Error::UnsupportedVersion);
+        }
+
+        Ok(Self {
+            dir: dir.to_owned(),
+        })
+    }
+
+    /// Returns the directory holding the fixture.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Runs a function with the Linux backend reading from the fixture instead of the system.
+    ///
+    /// Only the calling thread reads from the fixture.
+    /// # Note
+    /// On other platforms, the function reads from the system.
+    pub fn replay<T>(&self, f: impl FnOnce() -> T) -> T {
+        cfg_if! {
+            if #[cfg(unix)] {
+                crate::linux::replay_fixture(&self.dir, f)
+            } else {
+                f()
+            }
+        }
+    }
+
+    /// Retrieves the list of devices recorded in the fixture, as [`Devices::all`](crate::Devices::all) would.
+    /// # Errors
+    /// If the platform is unsupported or the recorded information could not be parsed, an error is returned.
+    pub fn devices(&self) -> Result<Vec<DeviceInfo>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                self.replay(crate::Devices::all)
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+}
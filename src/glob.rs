@@ -0,0 +1,95 @@
+/// Matches text against a shell-style pattern.
+///
+/// Supports `*`, `?` and character classes such as `[abc]`, `[a-z]` and `[!0-9]`.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // Position after the last `*` in the pattern, and the text position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+
+    let mut p = 0;
+    let mut t = 0;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            Some('[') => {
+                if let Some((matched, next)) = match_class(&pattern[p..], text[t]) {
+                    if matched {
+                        p += next;
+                        t += 1;
+                        continue;
+                    }
+                } else if text[t] == '[' {
+                    p += 1;
+                    t += 1;
+                    continue;
+                }
+            }
+            Some(&c) if c == text[t] => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        // Backtrack: let the last `*` consume one more character
+        match star {
+            Some((star_p, star_t)) => {
+                p = star_p;
+                t = star_t + 1;
+                star = Some((star_p, t));
+            }
+            None => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Matches a character against the class at the start of `pattern`.
+///
+/// Returns whether the character matched and the length of the class,
+/// or `None` if the class is not terminated.
+fn match_class(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 1;
+
+    let negated = matches!(pattern.get(i), Some('!' | '^'));
+
+    if negated {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+
+    loop {
+        let start = *pattern.get(i)?;
+
+        if start == ']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+
+        first = false;
+
+        if pattern.get(i + 1) == Some(&'-') && pattern.get(i + 2).is_some_and(|&e| e != ']') {
+            let end = pattern[i + 2];
+            matched |= (start..=end).contains(&c);
+            i += 3;
+        } else {
+            matched |= start == c;
+            i += 1;
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    edid::Edid,
+    info::DeviceInfo,
+};
+
+/// Connection status of a display connector.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ConnectorStatus {
+    /// A display is connected.
+    Connected,
+
+    /// No display is connected.
+    Disconnected,
+
+    /// The status could not be determined.
+    Unknown,
+}
+
+/// A display connector of a display adapter, such as `HDMI-A-1` or `DP-2`.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Connector {
+    pub(crate) name: String,
+    pub(crate) status: ConnectorStatus,
+    pub(crate) enabled: bool,
+    pub(crate) modes: Vec<String>,
+    pub(crate) edid: Option<Vec<u8>>,
+    pub(crate) monitor: Option<Edid>,
+}
+
+impl Connector {
+    /// Returns the name of the connector, such as `HDMI-A-1`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the connection status of the connector.
+    pub fn status(&self) -> ConnectorStatus {
+        self.status
+    }
+
+    /// Returns `true` if the connector is driving a display.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the modes supported by the connected display, such as `1920x1080`.
+    pub fn modes(&self) -> &[String] {
+        &self.modes
+    }
+
+    /// Returns the raw EDID of the connected display, if any.
+    pub fn edid(&self) -> Option<&[u8]> {
+        self.edid.as_deref()
+    }
+
+    /// Returns the identification and capabilities of the connected display, if its EDID is valid.
+    pub fn monitor(&self) -> Option<&Edid> {
+        self.monitor.as_ref()
+    }
+}
+
+/// A display adapter, along with its DRM nodes and connectors.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DisplayAdapter {
+    pub(crate) device: DeviceInfo,
+
+    pub(crate) boot_vga: bool,
+    pub(crate) cards: Vec<String>,
+    pub(crate) render_nodes: Vec<String>,
+    pub(crate) connectors: Vec<Connector>,
+}
+
+impl DisplayAdapter {
+    /// Returns the PCI device of the display adapter.
+    pub fn device(&self) -> &DeviceInfo {
+        &self.device
+    }
+
+    /// Returns `true` if the adapter was used by the firmware to display the boot console.
+    pub fn boot_vga(&self) -> bool {
+        self.boot_vga
+    }
+
+    /// Returns the paths of the primary DRM nodes of the adapter, such as `/dev/dri/card0`.
+    pub fn cards(&self) -> &[String] {
+        &self.cards
+    }
+
+    /// Returns the paths of the render nodes of the adapter, such as `/dev/dri/renderD128`.
+    pub fn render_nodes(&self) -> &[String] {
+        &self.render_nodes
+    }
+
+    /// Returns the display connectors of the adapter.
+    pub fn connectors(&self) -> &[Connector] {
+        &self.connectors
+    }
+}
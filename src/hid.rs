@@ -0,0 +1,599 @@
+use crate::{
+    error::Error,
+    info::DeviceInfo,
+};
+
+/// A HID usage, made up of a usage page and a usage id.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HidUsage {
+    /// Usage page, such as `0x01` (Generic Desktop) or `0xFF00` (vendor defined).
+    pub usage_page: u16,
+
+    /// Usage id within the usage page.
+    pub usage: u16,
+}
+
+/// The kind of a HID report.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ReportKind {
+    /// Sent from the device to the host.
+    Input,
+
+    /// Sent from the host to the device.
+    Output,
+
+    /// Exchanged on request in either direction.
+    Feature,
+}
+
+/// A field of a HID report, made up of one or more equally sized values.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ReportField {
+    pub(crate) usages: Vec<HidUsage>,
+    pub(crate) usage_range: Option<(HidUsage, HidUsage)>,
+    pub(crate) report_size: u32,
+    pub(crate) report_count: u32,
+    pub(crate) logical_minimum: i32,
+    pub(crate) logical_maximum: i32,
+    pub(crate) flags: u32,
+}
+
+impl ReportField {
+    /// Returns the usages assigned to the values of the field.
+    pub fn usages(&self) -> &[HidUsage] {
+        &self.usages
+    }
+
+    /// Returns the inclusive range of usages assigned to the values of the field, if any.
+    pub fn usage_range(&self) -> Option<(HidUsage, HidUsage)> {
+        self.usage_range
+    }
+
+    /// Returns the size of each value in bits.
+    pub fn report_size(&self) -> u32 {
+        self.report_size
+    }
+
+    /// Returns the number of values in the field.
+    pub fn report_count(&self) -> u32 {
+        self.report_count
+    }
+
+    /// Returns the total size of the field in bits, saturating at `u32::MAX`.
+    pub fn size_bits(&self) -> u32 {
+        self.report_size.saturating_mul(self.report_count)
+    }
+
+    /// Returns the minimum logical value of each value.
+    pub fn logical_minimum(&self) -> i32 {
+        self.logical_minimum
+    }
+
+    /// Returns the maximum logical value of each value.
+    pub fn logical_maximum(&self) -> i32 {
+        self.logical_maximum
+    }
+
+    /// Returns the raw flags of the main item that declared the field.
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    /// Returns `true` if the field is constant padding.
+    pub fn is_constant(&self) -> bool {
+        self.flags & 0x01 != 0
+    }
+
+    /// Returns `true` if each value has its own usage, as opposed to being an array of usage selectors.
+    pub fn is_variable(&self) -> bool {
+        self.flags & 0x02 != 0
+    }
+
+    /// Returns `true` if the values are relative to the previous report.
+    pub fn is_relative(&self) -> bool {
+        self.flags & 0x04 != 0
+    }
+}
+
+/// A HID report, made up of fields.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Report {
+    pub(crate) kind: ReportKind,
+    pub(crate) id: u8,
+    pub(crate) fields: Vec<ReportField>,
+}
+
+impl Report {
+    /// Returns the kind of the report.
+    pub fn kind(&self) -> ReportKind {
+        self.kind
+    }
+
+    /// Returns the report id, or `0` if the device does not use report ids.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Returns the fields of the report, in order.
+    pub fn fields(&self) -> &[ReportField] {
+        &self.fields
+    }
+
+    /// Returns the size of the report in bits, excluding the report id, saturating at `u32::MAX`.
+    pub fn size_bits(&self) -> u32 {
+        self.fields
+            .iter()
+            .fold(0, |acc: u32, f| acc.saturating_add(f.size_bits()))
+    }
+
+    /// Returns the size of the report in bytes, excluding the report id.
+    pub fn size(&self) -> u32 {
+        self.size_bits().div_ceil(8)
+    }
+}
+
+/// A parsed HID report descriptor.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ReportDescriptor {
+    pub(crate) collections: Vec<HidUsage>,
+    pub(crate) reports: Vec<Report>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct GlobalState {
+    usage_page: u16,
+    logical_minimum: i32,
+    logical_maximum: i32,
+    report_size: u32,
+    report_id: u8,
+    report_count: u32,
+}
+
+#[derive(Default)]
+struct LocalState {
+    usages: Vec<HidUsage>,
+    usage_minimum: Option<HidUsage>,
+    usage_maximum: Option<HidUsage>,
+}
+
+/// The data of a short item.
+struct ItemData<'a>(&'a [u8]);
+
+impl ItemData<'_> {
+    fn unsigned(&self) -> u32 {
+        self.0.iter().rev().fold(0, |acc, b| (acc << 8) | u32::from(*b))
+    }
+
+    fn signed(&self) -> i32 {
+        match *self.0 {
+            [a] => i32::from(i8::from_le_bytes([a])),
+            [a, b] => i32::from(i16::from_le_bytes([a, b])),
+            [a, b, c, d] => i32::from_le_bytes([a, b, c, d]),
+            _ => 0,
+        }
+    }
+
+    /// Usages may carry their own usage page in the upper 16 bits.
+    #[allow(clippy::cast_possible_truncation)]
+    fn usage(&self, usage_page: u16) -> HidUsage {
+        let value = self.unsigned();
+
+        HidUsage {
+            usage_page: if self.0.len() == 4 {
+                (value >> 16) as u16
+            } else {
+                usage_page
+            },
+            usage: value as u16,
+        }
+    }
+}
+
+impl GlobalState {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn apply(&mut self, tag: u8, data: &ItemData, stack: &mut Vec<Self>) -> Result<(), Error> {
+        match tag {
+            0x0 => self.usage_page = data.unsigned() as u16,
+            0x1 => self.logical_minimum = data.signed(),
+            // Logical maximum is unsigned when the minimum is not negative
+            0x2 => {
+                self.logical_maximum = if self.logical_minimum >= 0 {
+                    data.unsigned() as i32
+                } else {
+                    data.signed()
+                };
+            }
+            0x7 => self.report_size = data.unsigned(),
+            0x8 => self.report_id = u8::try_from(data.unsigned()).map_err(|_| Error::ParseError)?,
+            0x9 => self.report_count = data.unsigned(),
+            0xA => stack.push(*self),
+            0xB => *self = stack.pop().ok_or(Error::ParseError)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl ReportDescriptor {
+    /// Parse a raw HID report descriptor.
+    /// # Errors
+    /// If the descriptor is truncated or malformed, an error is returned.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        let mut descriptor = Self::default();
+
+        let mut global = GlobalState::default();
+        let mut stack = Vec::new();
+        let mut local = LocalState::default();
+        let mut depth = 0usize;
+
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let prefix = bytes[i];
+
+            // Long items are reserved and carry no information we use
+            if prefix == 0xFE {
+                let size = *bytes.get(i + 1).ok_or(Error::ParseError)? as usize;
+                i += 3 + size;
+
+                if i > bytes.len() {
+                    return Err(Error::ParseError);
+                }
+
+                continue;
+            }
+
+            let size = match prefix & 0x03 {
+                3 => 4,
+                n => n as usize,
+            };
+
+            let data = ItemData(bytes.get(i + 1..i + 1 + size).ok_or(Error::ParseError)?);
+            let tag = prefix >> 4;
+            i += 1 + size;
+
+            match (prefix >> 2) & 0x03 {
+                // Main items
+                0 => {
+                    match tag {
+                        0x8 => descriptor.push_field(ReportKind::Input, &global, &local, &data),
+                        0x9 => descriptor.push_field(ReportKind::Output, &global, &local, &data),
+                        0xB => descriptor.push_field(ReportKind::Feature, &global, &local, &data),
+                        0xA => {
+                            // Application collections at the top level identify the device
+                            if depth == 0 && data.unsigned() == 0x01 {
+                                let usage = local.usages.first().copied().unwrap_or(HidUsage {
+                                    usage_page: global.usage_page,
+                                    usage: 0,
+                                });
+
+                                descriptor.collections.push(usage);
+                            }
+
+                            depth += 1;
+                        }
+                        0xC => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+
+                    local = LocalState::default();
+                }
+                // Global items
+                1 => global.apply(tag, &data, &mut stack)?,
+                // Local items
+                2 => match tag {
+                    0x0 => local.usages.push(data.usage(global.usage_page)),
+                    0x1 => local.usage_minimum = Some(data.usage(global.usage_page)),
+                    0x2 => local.usage_maximum = Some(data.usage(global.usage_page)),
+                    _ => {}
+                },
+                _ => return Err(Error::ParseError),
+            }
+        }
+
+        Ok(descriptor)
+    }
+
+    fn push_field(
+        &mut self,
+        kind: ReportKind,
+        global: &GlobalState,
+        local: &LocalState,
+        data: &ItemData,
+    ) {
+        let field = ReportField {
+            usages: local.usages.clone(),
+            usage_range: local.usage_minimum.zip(local.usage_maximum),
+            report_size: global.report_size,
+            report_count: global.report_count,
+            logical_minimum: global.logical_minimum,
+            logical_maximum: global.logical_maximum,
+            flags: data.unsigned(),
+        };
+
+        self.report_mut(kind, global.report_id).fields.push(field);
+    }
+
+    fn report_mut(&mut self, kind: ReportKind, id: u8) -> &mut Report {
+        let position = self.reports.iter().position(|r| r.kind == kind && r.id == id);
+
+        let index = if let Some(index) = position {
+            index
+        } else {
+            self.reports.push(Report {
+                kind,
+                id,
+                fields: Vec::new(),
+            });
+
+            self.reports.len() - 1
+        };
+
+        &mut self.reports[index]
+    }
+
+    /// Returns the usages of the top-level application collections.
+    pub fn collections(&self) -> &[HidUsage] {
+        &self.collections
+    }
+
+    /// Returns the usage page of the first top-level application collection, if any.
+    pub fn usage_page(&self) -> Option<u16> {
+        self.collections.first().map(|c| c.usage_page)
+    }
+
+    /// Returns the usage of the first top-level application collection, if any.
+    pub fn usage(&self) -> Option<u16> {
+        self.collections.first().map(|c| c.usage)
+    }
+
+    /// Returns the reports declared by the descriptor.
+    pub fn reports(&self) -> &[Report] {
+        &self.reports
+    }
+}
+
+/// A HID device exposed through a `hidraw` node.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HidDevice {
+    pub(crate) name: String,
+    pub(crate) device: Option<DeviceInfo>,
+
+    pub(crate) hid_name: Option<String>,
+    pub(crate) bus_type: u16,
+    pub(crate) vendor_id: u16,
+    pub(crate) product_id: u16,
+
+    pub(crate) report_descriptor: ReportDescriptor,
+}
+
+impl HidDevice {
+    /// Returns the kernel name of the node, such as `hidraw0`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the device file, such as `/dev/hidraw0`.
+    pub fn devnode(&self) -> String {
+        format!("/dev/{}", self.name)
+    }
+
+    /// Returns the device the HID device belongs to, such as a USB or I2C device, if any.
+    ///
+    /// Bluetooth HID devices have no such device, rather than the controller they connect through.
+    pub fn device(&self) -> Option<&DeviceInfo> {
+        self.device.as_ref()
+    }
+
+    /// Returns the name reported by the HID device, if known.
+    pub fn hid_name(&self) -> Option<&str> {
+        self.hid_name.as_deref()
+    }
+
+    /// Returns the bus type, such as `0x03` (USB) or `0x05` (Bluetooth).
+    pub fn bus_type(&self) -> u16 {
+        self.bus_type
+    }
+
+    /// Returns the HID vendor id.
+    pub fn vendor_id(&self) -> u16 {
+        self.vendor_id
+    }
+
+    /// Returns the HID product id.
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+
+    /// Returns the parsed report descriptor.
+    pub fn report_descriptor(&self) -> &ReportDescriptor {
+        &self.report_descriptor
+    }
+
+    /// Returns the usage page of the first top-level application collection, if any.
+    pub fn usage_page(&self) -> Option<u16> {
+        self.report_descriptor.usage_page()
+    }
+
+    /// Returns the usage of the first top-level application collection, if any.
+    pub fn usage(&self) -> Option<u16> {
+        self.report_descriptor.usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A boot protocol mouse: three buttons, padding and relative X and Y.
+    const MOUSE: &[u8] = &[
+        0x05, 0x01, // Usage Page (Generic Desktop)
+        0x09, 0x02, // Usage (Mouse)
+        0xA1, 0x01, // Collection (Application)
+        0x09, 0x01, //   Usage (Pointer)
+        0xA1, 0x00, //   Collection (Physical)
+        0x05, 0x09, //     Usage Page (Button)
+        0x19, 0x01, //     Usage Minimum (1)
+        0x29, 0x03, //     Usage Maximum (3)
+        0x15, 0x00, //     Logical Minimum (0)
+        0x25, 0x01, //     Logical Maximum (1)
+        0x95, 0x03, //     Report Count (3)
+        0x75, 0x01, //     Report Size (1)
+        0x81, 0x02, //     Input (Data, Variable, Absolute)
+        0x95, 0x01, //     Report Count (1)
+        0x75, 0x05, //     Report Size (5)
+        0x81, 0x01, //     Input (Constant)
+        0x05, 0x01, //     Usage Page (Generic Desktop)
+        0x09, 0x30, //     Usage (X)
+        0x09, 0x31, //     Usage (Y)
+        0x15, 0x81, //     Logical Minimum (-127)
+        0x25, 0x7F, //     Logical Maximum (127)
+        0x75, 0x08, //     Report Size (8)
+        0x95, 0x02, //     Report Count (2)
+        0x81, 0x06, //     Input (Data, Variable, Relative)
+        0xC0, //       End Collection
+        0xC0, //     End Collection
+    ];
+
+    fn usage(usage_page: u16, usage: u16) -> HidUsage {
+        HidUsage { usage_page, usage }
+    }
+
+    #[test]
+    fn parses_mouse() {
+        let descriptor = ReportDescriptor::parse(MOUSE).unwrap();
+
+        assert_eq!(descriptor.collections(), [usage(0x01, 0x02)]);
+        assert_eq!(descriptor.reports().len(), 1);
+
+        let report = &descriptor.reports()[0];
+        assert_eq!(report.kind(), ReportKind::Input);
+        assert_eq!(report.id(), 0);
+        assert_eq!(report.size_bits(), 24);
+        assert_eq!(report.size(), 3);
+
+        let fields = report.fields();
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields[0].usage_range(), Some((usage(0x09, 1), usage(0x09, 3))));
+        assert!(fields[0].is_variable());
+        assert!(fields[1].is_constant());
+        assert_eq!(fields[2].usages(), [usage(0x01, 0x30), usage(0x01, 0x31)]);
+        assert_eq!(fields[2].logical_minimum(), -127);
+        assert_eq!(fields[2].logical_maximum(), 127);
+        assert!(fields[2].is_relative());
+    }
+
+    #[test]
+    fn splits_reports_by_id_and_kind() {
+        let bytes = [
+            0x05, 0x0C, // Usage Page (Consumer)
+            0x09, 0x01, // Usage (Consumer Control)
+            0xA1, 0x01, // Collection (Application)
+            0x85, 0x01, //   Report ID (1)
+            0x75, 0x10, //   Report Size (16)
+            0x95, 0x01, //   Report Count (1)
+            0x81, 0x00, //   Input (Data, Array)
+            0x85, 0x02, //   Report ID (2)
+            0x75, 0x08, //   Report Size (8)
+            0x95, 0x04, //   Report Count (4)
+            0x81, 0x02, //   Input (Data, Variable)
+            0xB1, 0x02, //   Feature (Data, Variable)
+            0x85, 0x01, //   Report ID (1)
+            0x81, 0x02, //   Input (Data, Variable)
+            0xC0, //     End Collection
+        ];
+
+        let descriptor = ReportDescriptor::parse(&bytes).unwrap();
+        let reports: Vec<_> = descriptor
+            .reports()
+            .iter()
+            .map(|r| (r.kind(), r.id(), r.fields().len(), r.size_bits()))
+            .collect();
+
+        assert_eq!(
+            reports,
+            [
+                (ReportKind::Input, 1, 2, 48),
+                (ReportKind::Input, 2, 1, 32),
+                (ReportKind::Feature, 2, 1, 32),
+            ]
+        );
+    }
+
+    #[test]
+    fn push_and_pop_restore_global_state() {
+        let bytes = [
+            0x75, 0x08, // Report Size (8)
+            0x95, 0x02, // Report Count (2)
+            0xA4, //       Push
+            0x75, 0x01, // Report Size (1)
+            0x95, 0x10, // Report Count (16)
+            0x81, 0x02, // Input (Data, Variable)
+            0xB4, //       Pop
+            0x81, 0x02, // Input (Data, Variable)
+        ];
+
+        let descriptor = ReportDescriptor::parse(&bytes).unwrap();
+        let fields = descriptor.reports()[0].fields();
+
+        assert_eq!((fields[0].report_size(), fields[0].report_count()), (1, 16));
+        assert_eq!((fields[1].report_size(), fields[1].report_count()), (8, 2));
+    }
+
+    #[test]
+    fn pop_without_push_is_an_error() {
+        assert!(ReportDescriptor::parse(&[0xB4]).is_err());
+    }
+
+    #[test]
+    fn skips_long_items() {
+        let bytes = [
+            0xFE, 0x02, 0x10, 0xAA, 0xBB, // Long item with 2 data bytes
+            0x75, 0x08, //                   Report Size (8)
+            0x95, 0x01, //                   Report Count (1)
+            0x81, 0x02, //                   Input (Data, Variable)
+        ];
+
+        let descriptor = ReportDescriptor::parse(&bytes).unwrap();
+
+        assert_eq!(descriptor.reports()[0].size_bits(), 8);
+    }
+
+    #[test]
+    fn rejects_truncated_items() {
+        // Long item claiming more data than present
+        assert!(ReportDescriptor::parse(&[0xFE, 0x04, 0x10, 0xAA]).is_err());
+        // Short item missing its data byte
+        assert!(ReportDescriptor::parse(&[0x75]).is_err());
+    }
+
+    #[test]
+    fn size_saturates() {
+        let bytes = [
+            0x77, 0xFF, 0xFF, 0xFF, 0xFF, // Report Size (0xFFFFFFFF)
+            0x97, 0xFF, 0xFF, 0xFF, 0xFF, // Report Count (0xFFFFFFFF)
+            0x81, 0x02, //                   Input (Data, Variable)
+            0x81, 0x02, //                   Input (Data, Variable)
+        ];
+
+        let descriptor = ReportDescriptor::parse(&bytes).unwrap();
+        let report = &descriptor.reports()[0];
+
+        assert_eq!(report.fields()[0].size_bits(), u32::MAX);
+        assert_eq!(report.size_bits(), u32::MAX);
+        assert_eq!(report.size(), u32::MAX.div_ceil(8));
+    }
+}
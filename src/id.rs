@@ -0,0 +1,248 @@
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+use crate::{
+    DeviceInfo,
+    DevicePath,
+    Error,
+};
+
+/// How reliably a [`DeviceId`] tells devices apart.
+///
+/// Stronger identities compare greater.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum IdStrength {
+    /// Based on the vendor and product only.
+    ///
+    /// Identical devices share the identity, and it may change when the device moves.
+    Ambiguous,
+
+    /// Based on where the device is attached.
+    ///
+    /// Survives reboots, but follows the port or slot rather than the device.
+    Path,
+
+    /// Based on the serial number of the device.
+    ///
+    /// Survives reboots and moving the device to another port.
+    Serial,
+}
+
+impl IdStrength {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Ambiguous => "ambiguous",
+            Self::Path => "path",
+            Self::Serial => "serial",
+        }
+    }
+}
+
+impl fmt::Display for IdStrength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A persistent identity of a device, comparable across reboots and reconnections.
+///
+/// Derived from a [`DeviceInfo`] by the first rule that applies:
+/// 1. Devices with a serial number, from [`DeviceInfo::serial`] or the udev `ID_SERIAL_SHORT`
+///    property, are identified by bus, vendor id, product id and serial number.
+///    The strength is [`IdStrength::Serial`].
+/// 2. Devices with a stable location are identified by bus, vendor id, product id and location.
+///    The location is the udev `ID_PATH` property if known, such as `pci-0000:00:14.0-usb-0:2`.
+///    Otherwise, it is derived from the path of devices on buses other than USB, in the same form,
+///    such as `pci-0000:00:1f.3` or `platform-serial8250`. The strength is [`IdStrength::Path`].
+/// 3. Other devices, such as USB devices without a serial number on systems without udev,
+///    are identified by bus, vendor id and product id. The strength is [`IdStrength::Ambiguous`].
+///
+/// USB devices are only located through `ID_PATH`, as USB bus numbers depend on the order
+/// controllers were probed in.
+/// Whitespace in serial numbers is replaced with `_`.
+///
+/// Identities format as `<strength>:<bus>:<vendor id>:<product id>[:<serial or location>]`,
+/// such as `serial:usb:046d:c52b:4A1B2C3D`, and parse back from that form.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DeviceId {
+    pub(crate) strength: IdStrength,
+    pub(crate) bus: String,
+    pub(crate) vendor_id: u16,
+    pub(crate) product_id: u16,
+    pub(crate) key: Option<String>,
+}
+
+/// Returns the location of a device in the form of the udev `ID_PATH` property.
+fn location(path: &DevicePath) -> Option<String> {
+    match path {
+        DevicePath::PCI {
+            domain,
+            bus,
+            slot,
+            function,
+        } => Some(format!("pci-{domain:04x}:{bus:02x}:{slot:02x}.{function:x}")),
+        DevicePath::USB { .. } => None,
+        DevicePath::Platform { name }
+        | DevicePath::I2C { name }
+        | DevicePath::SPI { name }
+        | DevicePath::SDIO { name }
+        | DevicePath::Thunderbolt { name } => Some(format!("{}-{name}", path.bus())),
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+
+    (!value.is_empty()).then(|| value.split_whitespace().collect::<Vec<_>>().join("_"))
+}
+
+impl DeviceId {
+    /// Derives the identity of a device.
+    pub fn new(device: &DeviceInfo) -> Self {
+        let serial = device
+            .serial()
+            .or_else(|| device.udev_property("ID_SERIAL_SHORT"))
+            .and_then(non_empty);
+
+        let location = || {
+            device
+                .udev_property("ID_PATH")
+                .and_then(non_empty)
+                .or_else(|| location(device.path()))
+        };
+
+        let (strength, key) = if let Some(serial) = serial {
+            (IdStrength::Serial, Some(serial))
+        } else if let Some(location) = location() {
+            (IdStrength::Path, Some(location))
+        } else {
+            (IdStrength::Ambiguous, None)
+        };
+
+        Self {
+            strength,
+            bus: device.path().bus().to_owned(),
+            vendor_id: device.vendor_id(),
+            product_id: device.product_id(),
+            key,
+        }
+    }
+
+    /// Returns how reliably the identity tells devices apart.
+    pub fn strength(&self) -> IdStrength {
+        self.strength
+    }
+
+    /// Returns the bus of the device, such as `pci` or `usb`.
+    pub fn bus(&self) -> &str {
+        &self.bus
+    }
+
+    /// Returns the id of the device's vendor.
+    pub fn vendor_id(&self) -> u16 {
+        self.vendor_id
+    }
+
+    /// Returns the device's product id.
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+
+    /// Returns the serial number the identity is based on, if any.
+    pub fn serial(&self) -> Option<&str> {
+        match self.strength {
+            IdStrength::Serial => self.key.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the location the identity is based on, if any.
+    pub fn location(&self) -> Option<&str> {
+        match self.strength {
+            IdStrength::Path => self.key.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl From<&DeviceInfo> for DeviceId {
+    fn from(device: &DeviceInfo) -> Self {
+        Self::new(device)
+    }
+}
+
+impl fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{:04x}:{:04x}",
+            self.strength, self.bus, self.vendor_id, self.product_id
+        )?;
+
+        if let Some(key) = &self.key {
+            write!(f, ":{key}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for DeviceId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(5, ':');
+        let mut next = || parts.next().ok_or(Error::ParseError);
+
+        let strength = match next()? {
+            "ambiguous" => IdStrength::Ambiguous,
+            "path" => IdStrength::Path,
+            "serial" => IdStrength::Serial,
+            _ => return Err(Error::ParseError),
+        };
+
+        let bus = next()?;
+        let vendor_id = u16::from_str_radix(next()?, 16).map_err(|_| Error::ParseError)?;
+        let product_id = u16::from_str_radix(next()?, 16).map_err(|_| Error::ParseError)?;
+        let key = next().ok();
+
+        let valid = !bus.is_empty()
+            && match strength {
+                IdStrength::Ambiguous => key.is_none(),
+                _ => key.is_some_and(|k| !k.is_empty()),
+            };
+
+        if !valid {
+            return Err(Error::ParseError);
+        }
+
+        Ok(Self {
+            strength,
+            bus: bus.to_owned(),
+            vendor_id,
+            product_id,
+            key: key.map(str::to_owned),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for DeviceId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DeviceId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
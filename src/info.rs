@@ -1,4 +1,10 @@
-use crate::path::DevicePath;
+use std::collections::BTreeMap;
+
+use crate::{
+    id::DeviceId,
+    path::DevicePath,
+    resource::PciResource,
+};
 
 /// Device information.
 /// 
@@ -18,6 +24,19 @@ pub struct DeviceInfo {
     pub(crate) vendor_id: u16,
     pub(crate) product_id: u16,
     pub(crate) manufacturer_id: Option<u16>,
+
+    pub(crate) serial: Option<String>,
+
+    pub(crate) numa_node: Option<u16>,
+    pub(crate) local_cpus: Option<Vec<usize>>,
+
+    pub(crate) resources: Vec<PciResource>,
+
+    pub(crate) compatible: Vec<String>,
+    pub(crate) acpi_hid: Option<String>,
+
+    pub(crate) udev_properties: BTreeMap<String, String>,
+    pub(crate) udev_tags: Vec<String>,
 }
 
 impl DeviceInfo {
@@ -28,6 +47,13 @@ impl DeviceInfo {
         &self.path
     }
 
+    /// Returns the persistent identity of the device.
+    ///
+    /// See [`DeviceId`] for how it is derived.
+    pub fn id(&self) -> DeviceId {
+        DeviceId::new(self)
+    }
+
     /// Returns the class name of the device.
     pub fn class(&self) -> &str {
         &self.class
@@ -73,4 +99,68 @@ impl DeviceInfo {
     pub fn manufacturer_id(&self) -> Option<u16> {
         self.manufacturer_id
     }
+
+    /// Returns the serial number of the device, if known.
+    /// # Note
+    /// Always returns `None` for devices other than USB devices, and on Windows.
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+
+    /// Returns the NUMA node the device is attached to, if known.
+    /// # Note
+    /// Always returns `None` for USB devices, on Windows, and on systems without NUMA.
+    pub fn numa_node(&self) -> Option<u16> {
+        self.numa_node
+    }
+
+    /// Returns the CPUs local to the device, if known.
+    /// # Note
+    /// Always returns `None` for USB devices and on Windows.
+    pub fn local_cpus(&self) -> Option<&[usize]> {
+        self.local_cpus.as_deref()
+    }
+
+    /// Returns the memory and I/O resources of the device.
+    /// # Note
+    /// Always empty for USB devices and on Windows.
+    pub fn resources(&self) -> &[PciResource] {
+        &self.resources
+    }
+
+    /// Returns the device tree `compatible` strings of the device, most specific first.
+    /// # Note
+    /// Always empty for PCI and USB devices, on Windows, and on systems without a device tree.
+    pub fn compatible(&self) -> &[String] {
+        &self.compatible
+    }
+
+    /// Returns the ACPI hardware id of the device, such as `PNP0501`, if known.
+    /// # Note
+    /// Always returns `None` for PCI and USB devices and on Windows.
+    pub fn acpi_hid(&self) -> Option<&str> {
+        self.acpi_hid.as_deref()
+    }
+
+    /// Returns the properties udev recorded for the device, such as `ID_MODEL_FROM_DATABASE`,
+    /// `ID_SERIAL` or `ID_PATH`.
+    /// # Note
+    /// Always empty on Windows, and on systems without udev.
+    pub fn udev_properties(&self) -> &BTreeMap<String, String> {
+        &self.udev_properties
+    }
+
+    /// Returns the value of a property udev recorded for the device, if set.
+    /// # Note
+    /// Always returns `None` on Windows, and on systems without udev.
+    pub fn udev_property(&self, key: &str) -> Option<&str> {
+        self.udev_properties.get(key).map(String::as_str)
+    }
+
+    /// Returns the tags udev recorded for the device, such as `systemd` or `uaccess`.
+    /// # Note
+    /// Always empty on Windows, and on systems without udev.
+    pub fn udev_tags(&self) -> &[String] {
+        &self.udev_tags
+    }
 }
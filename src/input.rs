@@ -0,0 +1,220 @@
+use crate::info::DeviceInfo;
+
+// Event types, see `input-event-codes.h`
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const EV_ABS: u16 = 0x03;
+
+const KEY_ESC: u16 = 1;
+const KEY_A: u16 = 30;
+const KEY_Z: u16 = 44;
+const BTN_LEFT: u16 = 0x110;
+const BTN_JOYSTICK: u16 = 0x120;
+const BTN_DIGI: u16 = 0x140;
+const BTN_TOOL_FINGER: u16 = 0x145;
+const BTN_TOUCH: u16 = 0x14a;
+
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+
+/// Event codes supported by an input device, decoded from its capability bitmasks.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct InputCapabilities {
+    pub(crate) properties: Vec<u16>,
+    pub(crate) events: Vec<u16>,
+    pub(crate) keys: Vec<u16>,
+    pub(crate) relative: Vec<u16>,
+    pub(crate) absolute: Vec<u16>,
+    pub(crate) misc: Vec<u16>,
+    pub(crate) leds: Vec<u16>,
+    pub(crate) switches: Vec<u16>,
+}
+
+impl InputCapabilities {
+    /// Returns the supported input properties (`INPUT_PROP_*`).
+    pub fn properties(&self) -> &[u16] {
+        &self.properties
+    }
+
+    /// Returns the supported event types (`EV_*`).
+    pub fn events(&self) -> &[u16] {
+        &self.events
+    }
+
+    /// Returns the supported key and button codes (`KEY_*`, `BTN_*`).
+    pub fn keys(&self) -> &[u16] {
+        &self.keys
+    }
+
+    /// Returns the supported relative axes (`REL_*`).
+    pub fn relative(&self) -> &[u16] {
+        &self.relative
+    }
+
+    /// Returns the supported absolute axes (`ABS_*`).
+    pub fn absolute(&self) -> &[u16] {
+        &self.absolute
+    }
+
+    /// Returns the supported miscellaneous events (`MSC_*`).
+    pub fn misc(&self) -> &[u16] {
+        &self.misc
+    }
+
+    /// Returns the supported LEDs (`LED_*`).
+    pub fn leds(&self) -> &[u16] {
+        &self.leds
+    }
+
+    /// Returns the supported switches (`SW_*`).
+    pub fn switches(&self) -> &[u16] {
+        &self.switches
+    }
+
+    fn has_event(&self, event: u16) -> bool {
+        self.events.contains(&event)
+    }
+
+    fn has_key(&self, key: u16) -> bool {
+        self.keys.contains(&key)
+    }
+
+    fn has_keys_in(&self, start: u16, end: u16) -> bool {
+        self.keys.iter().any(|k| (start..end).contains(k))
+    }
+}
+
+/// An input device, along with the USB or PCI device it belongs to.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct InputDevice {
+    pub(crate) name: String,
+    pub(crate) device: Option<DeviceInfo>,
+
+    pub(crate) input_name: Option<String>,
+    pub(crate) phys: Option<String>,
+    pub(crate) uniq: Option<String>,
+
+    pub(crate) bus_type: u16,
+    pub(crate) vendor_id: u16,
+    pub(crate) product_id: u16,
+    pub(crate) version: u16,
+
+    pub(crate) handlers: Vec<String>,
+    pub(crate) capabilities: InputCapabilities,
+}
+
+impl InputDevice {
+    /// Returns the kernel name of the input device, such as `input5`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the USB or PCI device the input device belongs to, if any.
+    ///
+    /// Built-in devices, such as PS/2 keyboards, have no such device.
+    pub fn device(&self) -> Option<&DeviceInfo> {
+        self.device.as_ref()
+    }
+
+    /// Returns the name reported by the input device, if known.
+    pub fn input_name(&self) -> Option<&str> {
+        self.input_name.as_deref()
+    }
+
+    /// Returns the physical path of the input device, if known.
+    pub fn phys(&self) -> Option<&str> {
+        self.phys.as_deref()
+    }
+
+    /// Returns the unique identifier of the input device, such as a serial number, if known.
+    pub fn uniq(&self) -> Option<&str> {
+        self.uniq.as_deref()
+    }
+
+    /// Returns the bus type, such as `0x03` (USB) or `0x11` (i8042).
+    pub fn bus_type(&self) -> u16 {
+        self.bus_type
+    }
+
+    /// Returns the input vendor id.
+    pub fn vendor_id(&self) -> u16 {
+        self.vendor_id
+    }
+
+    /// Returns the input product id.
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+
+    /// Returns the input device version.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// Returns the handlers attached to the device, such as `event3`, `mouse0` or `kbd`.
+    pub fn handlers(&self) -> &[String] {
+        &self.handlers
+    }
+
+    /// Returns the paths of the device files exposed by the device, such as `/dev/input/event3`.
+    pub fn devnodes(&self) -> Vec<String> {
+        self.handlers
+            .iter()
+            .filter(|h| h.starts_with("event") || h.starts_with("mouse") || h.starts_with("js"))
+            .map(|h| format!("/dev/input/{h}"))
+            .collect()
+    }
+
+    /// Returns the decoded capabilities of the device.
+    pub fn capabilities(&self) -> &InputCapabilities {
+        &self.capabilities
+    }
+
+    /// Returns `true` if the device has a typing keyboard.
+    pub fn is_keyboard(&self) -> bool {
+        let caps = &self.capabilities;
+
+        caps.has_event(EV_KEY) && caps.has_key(KEY_ESC) && caps.has_keys_in(KEY_A, KEY_Z + 1)
+    }
+
+    /// Returns `true` if the device is a mouse or other relative pointing device.
+    pub fn is_mouse(&self) -> bool {
+        let caps = &self.capabilities;
+
+        caps.has_event(EV_REL)
+            && caps.relative.contains(&REL_X)
+            && caps.relative.contains(&REL_Y)
+            && caps.has_key(BTN_LEFT)
+    }
+
+    /// Returns `true` if the device is a touchscreen.
+    pub fn is_touchscreen(&self) -> bool {
+        let caps = &self.capabilities;
+
+        self.has_absolute_pointer() && caps.has_key(BTN_TOUCH) && !caps.has_key(BTN_TOOL_FINGER)
+    }
+
+    /// Returns `true` if the device is a touchpad.
+    pub fn is_touchpad(&self) -> bool {
+        self.has_absolute_pointer() && self.capabilities.has_key(BTN_TOOL_FINGER)
+    }
+
+    /// Returns `true` if the device is a joystick or game controller.
+    pub fn is_joystick(&self) -> bool {
+        let caps = &self.capabilities;
+
+        caps.has_event(EV_KEY) && caps.has_keys_in(BTN_JOYSTICK, BTN_DIGI)
+    }
+
+    fn has_absolute_pointer(&self) -> bool {
+        let caps = &self.capabilities;
+
+        caps.has_event(EV_ABS) && caps.absolute.contains(&ABS_X) && caps.absolute.contains(&ABS_Y)
+    }
+}
@@ -5,14 +5,122 @@
 #![allow(clippy::redundant_closure_for_method_calls)]
 #![doc = include_str!("../README.md")]
 
+mod block;
+mod bluetooth;
+mod duplicate;
+mod edid;
 mod error;
+#[cfg(any(feature = "json", feature = "yaml", feature = "csv"))]
+pub mod export;
+mod fixture;
+mod glob;
+mod gpu;
+mod hid;
+mod id;
 mod info;
+mod input;
+mod net;
+mod node;
 mod path;
+mod power;
+mod resource;
+mod selector;
+mod serial;
+mod snapshot;
+mod sound;
+mod thunderbolt;
+mod udev;
 
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+};
+
+pub use block::{
+    BlockDevice,
+    Partition,
+};
+pub use bluetooth::BluetoothController;
 use cfg_if::cfg_if;
-pub use error::Error;
+pub use duplicate::{
+    DuplicateGroup,
+    DuplicateMember,
+};
+pub use edid::{
+    CeaExtension,
+    DisplayMode,
+    Edid,
+};
+pub use error::{
+    Error,
+    ParsePathError,
+    ParseRulesError,
+    ParseSelectorError,
+    RuleErrorKind,
+    SelectorErrorKind,
+};
+pub use fixture::Fixture;
+pub use gpu::{
+    Connector,
+    ConnectorStatus,
+    DisplayAdapter,
+};
+pub use hid::{
+    HidDevice,
+    HidUsage,
+    Report,
+    ReportDescriptor,
+    ReportField,
+    ReportKind,
+};
+pub use id::{
+    DeviceId,
+    IdStrength,
+};
 pub use info::DeviceInfo;
+pub use input::{
+    InputCapabilities,
+    InputDevice,
+};
+pub use net::{
+    NetworkAdapter,
+    OperState,
+};
+pub use node::DeviceNode;
 pub use path::DevicePath;
+pub use power::{
+    DevicePowerState,
+    PowerControl,
+    PowerState,
+    RuntimeStatus,
+};
+pub use resource::{
+    PciResource,
+    PCI_ROM_RESOURCE,
+};
+pub use selector::Selector;
+pub use serial::SerialPort;
+pub use snapshot::{
+    HostInfo,
+    Snapshot,
+    SNAPSHOT_VERSION,
+};
+pub use sound::{
+    HdaCodec,
+    PcmDevice,
+    SoundCard,
+};
+pub use thunderbolt::{
+    ThunderboltAuthorization,
+    ThunderboltDevice,
+    ThunderboltDomain,
+    ThunderboltSecurity,
+};
+pub use udev::{
+    UdevData,
+    UdevEvaluation,
+    UdevRules,
+};
 
 #[cfg(unix)]
 mod linux;
@@ -26,7 +134,7 @@ pub struct Devices {
 }
 
 impl Devices {
-    /// Retrieve a list of all connected devices.
+    /// Retrieve a list of all connected PCI and USB devices.
     /// # Errors
     /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
     pub fn get() -> Result<Vec<DeviceInfo>, Error> {
@@ -36,6 +144,46 @@ impl Devices {
         Ok(devices)
     }
 
+    /// Retrieve a list of all connected devices on every supported bus.
+    ///
+    /// On Linux, this adds platform, I2C, SPI, SDIO and Thunderbolt devices to [`Devices::get`].
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn all() -> Result<Vec<DeviceInfo>, Error> {
+        let mut devices = Self::get()?;
+
+        #[cfg(unix)]
+        {
+            devices.extend(Self::platform()?);
+            devices.extend(Self::i2c()?);
+            devices.extend(Self::spi()?);
+            devices.extend(Self::sdio()?);
+            devices.extend(Self::thunderbolt()?);
+        }
+
+        Ok(devices)
+    }
+
+    /// Retrieve all connected devices on every supported bus matched by a selector.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn select(selector: &Selector) -> Result<Vec<DeviceInfo>, Error> {
+        let mut devices = Self::all()?;
+        devices.retain(|d| selector.matches(d));
+
+        Ok(devices)
+    }
+
+    /// Retrieve groups of connected devices that share a vendor, product and serial number,
+    /// ordered by where they are attached.
+    ///
+    /// See [`DuplicateGroup::find`] for how devices are ordered.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn duplicates() -> Result<Vec<DuplicateGroup>, Error> {
+        Ok(DuplicateGroup::find(&Self::all()?))
+    }
+
     /// Retrieve a list of all connected PCI devices.
     /// # Errors
     /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
@@ -51,6 +199,37 @@ impl Devices {
         }
     }
 
+    /// Retrieve all connected PCI devices, grouped by NUMA node.
+    ///
+    /// Devices without a known NUMA node are grouped under `None`.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn pci_by_numa_node() -> Result<BTreeMap<Option<u16>, Vec<DeviceInfo>>, Error> {
+        let mut nodes: BTreeMap<_, Vec<_>> = BTreeMap::new();
+
+        for device in Self::pci()? {
+            nodes.entry(device.numa_node()).or_default().push(device);
+        }
+
+        Ok(nodes)
+    }
+
+    /// Returns the path of the file that maps a PCI resource, suitable for `mmap`.
+    ///
+    /// On Linux this is `resourceN` (or `rom` for [`PCI_ROM_RESOURCE`]) in the device's sysfs directory.
+    /// # Errors
+    /// If the platform is unsupported, or the device or resource does not exist, an error is returned.
+    pub fn pci_resource_path(path: &DevicePath, index: u8) -> Result<PathBuf, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_pci_resource_path(path, index)
+            } else {
+                let _ = (path, index);
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
     /// Retrieve a list of all connected USB devices.
     /// # Errors
     /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
@@ -65,4 +244,264 @@ impl Devices {
             }
         }
     }
+
+    /// Retrieve the path of the device a device is connected to, such as the hub of a USB device.
+    ///
+    /// Returns `None` for devices attached directly to the system, such as PCI devices on the root bus.
+    /// # Errors
+    /// If the platform is unsupported or the device does not exist, an error is returned.
+    pub fn parent(path: &DevicePath) -> Result<Option<DevicePath>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_parent(path)
+            } else {
+                let _ = path;
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve the device nodes and network interfaces belonging to a device.
+    ///
+    /// Nodes belonging to other PCI or USB devices further down the tree are not included.
+    /// # Errors
+    /// If the platform is unsupported or the device does not exist, an error is returned.
+    pub fn nodes(path: &DevicePath) -> Result<Vec<DeviceNode>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_nodes(path)
+            } else {
+                let _ = path;
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of all connected platform devices.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn platform() -> Result<Vec<DeviceInfo>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_bus("platform")
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of all connected I2C devices.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn i2c() -> Result<Vec<DeviceInfo>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_bus("i2c")
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of all connected SPI devices.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn spi() -> Result<Vec<DeviceInfo>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_bus("spi")
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of all connected SDIO devices.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn sdio() -> Result<Vec<DeviceInfo>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_bus("sdio")
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of sound cards, along with the device that provides each card.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of sound cards, an error is returned.
+    pub fn sound_cards() -> Result<Vec<SoundCard>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_sound_cards()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of all connected Thunderbolt and USB4 routers, including host routers.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn thunderbolt() -> Result<Vec<DeviceInfo>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_bus("thunderbolt")
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of Thunderbolt domains, with their routers and the PCI devices tunneled through them.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of domains, an error is returned.
+    pub fn thunderbolt_domains() -> Result<Vec<ThunderboltDomain>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_thunderbolt_domains()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of block storage devices backed by PCI or USB devices.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of block devices, an error is returned.
+    pub fn block_devices() -> Result<Vec<BlockDevice>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_block_devices()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of Bluetooth controllers, along with the device that provides each controller.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of controllers, an error is returned.
+    pub fn bluetooth() -> Result<Vec<BluetoothController>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_bluetooth_controllers()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of PCI display adapters, along with their DRM nodes and connectors.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn display_adapters() -> Result<Vec<DisplayAdapter>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_display_adapters()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of HID devices exposed through `hidraw` nodes, with their parsed report descriptors.
+    ///
+    /// Devices whose id or report descriptor cannot be read or parsed are left out.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of HID devices, an error is returned.
+    pub fn hid() -> Result<Vec<HidDevice>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_hid_devices()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of input devices, such as keyboards, mice, touchscreens and game controllers.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of input devices, an error is returned.
+    pub fn input() -> Result<Vec<InputDevice>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_input_devices()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of network interfaces backed by PCI or USB devices.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of interfaces, an error is returned.
+    pub fn network_adapters() -> Result<Vec<NetworkAdapter>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_network_adapters()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve a list of serial ports, along with the USB or PCI device that provides each port.
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of serial ports, an error is returned.
+    pub fn serial_ports() -> Result<Vec<SerialPort>, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_serial_ports()
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Retrieve the power management state of a device.
+    /// # Errors
+    /// If the platform is unsupported or the device does not exist, an error is returned.
+    pub fn power_state(path: &DevicePath) -> Result<PowerState, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::get_power_state(path)
+            } else {
+                let _ = path;
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Set the runtime power management policy of a device.
+    /// # Errors
+    /// If the platform is unsupported, the device does not exist, or the policy could not be written, an error is returned.
+    pub fn set_power_control(path: &DevicePath, control: PowerControl) -> Result<(), Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::set_power_control(path, control)
+            } else {
+                let _ = (path, control);
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Set the autosuspend delay of a device in milliseconds.
+    ///
+    /// A negative delay prevents the device from being autosuspended.
+    /// # Errors
+    /// If the platform is unsupported, the device does not exist, or the delay could not be written, an error is returned.
+    pub fn set_autosuspend_delay(path: &DevicePath, delay_ms: i32) -> Result<(), Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                linux::set_autosuspend_delay(path, delay_ms)
+            } else {
+                let _ = (path, delay_ms);
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
 }
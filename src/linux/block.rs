@@ -0,0 +1,238 @@
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use super::{
+    fixture,
+    sysfs,
+    DeviceLookup,
+};
+use crate::{
+    block::{
+        BlockDevice,
+        Partition,
+    },
+    error::Error,
+    path::DevicePath,
+};
+
+const SYSFS_BLOCK: &str = "/sys/block";
+
+// Sizes in sysfs are always in 512-byte sectors
+const SECTOR_SIZE: u64 = 512;
+
+fn sectors(dir: &Path, name: &str) -> Option<u64> {
+    sysfs::read_attr(dir, name)
+        .and_then(|s| s.parse::<u64>().ok())
+        .and_then(|s| s.checked_mul(SECTOR_SIZE))
+}
+
+/// Extract the serial number from SCSI VPD page 0x80 (Unit Serial Number).
+fn vpd_serial(dir: &Path) -> Option<String> {
+    let page = fs::read(dir.join("vpd_pg80")).ok()?;
+    let serial = page.get(4..)?;
+
+    Some(String::from_utf8_lossy(serial).trim().to_owned()).filter(|s| !s.is_empty())
+}
+
+fn partitions(dir: &Path) -> Vec<Partition> {
+    let mut partitions = Vec::new();
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return partitions;
+    };
+
+    for entry in entries.flatten() {
+        let part = entry.path();
+
+        let Some(number) = sysfs::read_attr(&part, "partition").and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+
+        partitions.push(Partition {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            number,
+            start: sectors(&part, "start").unwrap_or_default(),
+            size: sectors(&part, "size").unwrap_or_default(),
+        });
+    }
+
+    partitions.sort_by_key(|p| p.number);
+    partitions
+}
+
+/// Returns the entries of a directory, sorted by name.
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+
+    entries.sort();
+    entries
+}
+
+/// Returns `true` for NVM Express controllers, named `nvme<n>`.
+fn is_nvme_controller(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str()?.strip_prefix("nvme"))
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Returns the device a disk belongs to.
+///
+/// With NVM Express native multipath, a namespace sits under a virtual `nvme-subsystem` device
+/// instead of its controller, so the controller is found through the namespace's path devices,
+/// such as `nvme0c0n1`, or through the controllers the subsystem links to.
+fn owning_device(dir: &Path) -> Option<DevicePath> {
+    if let Some(path) = sysfs::owning_device(&dir.join("device")) {
+        return Some(path);
+    }
+
+    let controllers = sorted_entries(&dir.join("device"))
+        .into_iter()
+        .filter(|p| is_nvme_controller(p));
+
+    sorted_entries(&dir.join("multipath"))
+        .into_iter()
+        .chain(controllers)
+        .find_map(|p| sysfs::owning_device(&p))
+}
+
+pub(crate) fn get_block_devices() -> Result<Vec<BlockDevice>, Error> {
+    let entries = fs::read_dir(fixture::system_path(SYSFS_BLOCK)).map_err(|_| Error::CommandError)?;
+
+    let mut lookup = DeviceLookup::default();
+    let mut disks = Vec::new();
+
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let parent = dir.join("device");
+
+        // Loop devices, RAM disks and other virtual disks have no backing device
+        let Some(path) = owning_device(&dir) else {
+            continue;
+        };
+
+        let Some(device) = lookup.find(&path)? else {
+            continue;
+        };
+
+        let model = sysfs::read_attr(&parent, "model").filter(|m| !m.is_empty());
+
+        let serial = sysfs::read_attr(&dir, "serial")
+            .or_else(|| sysfs::read_attr(&parent, "serial"))
+            .filter(|s| !s.is_empty())
+            .or_else(|| vpd_serial(&parent));
+
+        let wwn = sysfs::read_attr(&dir, "wwid")
+            .or_else(|| sysfs::read_attr(&parent, "wwid"))
+            .filter(|w| !w.is_empty());
+
+        disks.push(BlockDevice {
+            device,
+            name: entry.file_name().to_string_lossy().into_owned(),
+            size: sectors(&dir, "size").unwrap_or_default(),
+            model,
+            serial,
+            wwn,
+            rotational: sysfs::read_attr(&dir, "queue/rotational").as_deref() == Some("1"),
+            removable: sysfs::read_attr(&dir, "removable").as_deref() == Some("1"),
+            partitions: partitions(&dir),
+        });
+    }
+
+    disks.sort();
+
+    Ok(disks)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::symlink;
+
+    use super::*;
+
+    fn tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("devices-blk-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    /// An NVM Express controller at `0000:3d:00.0` with a path device, and a multipath namespace.
+    fn nvme(root: &Path) -> (PathBuf, PathBuf) {
+        let pci = root.join("devices/pci0000:00/0000:00:1d.0/0000:3d:00.0");
+        let controller = pci.join("nvme/nvme0");
+        let subsystem = root.join("devices/virtual/nvme-subsystem/nvme-subsys0");
+        let namespace = subsystem.join("nvme0n1");
+
+        fs::create_dir_all(controller.join("nvme0c0n1")).unwrap();
+        fs::create_dir_all(&namespace).unwrap();
+        symlink(root.join("bus/pci"), pci.join("subsystem")).unwrap();
+        symlink(root.join("class/nvme"), controller.join("subsystem")).unwrap();
+        symlink(&subsystem, namespace.join("device")).unwrap();
+
+        (namespace, controller)
+    }
+
+    #[test]
+    fn multipath_namespaces_belong_to_their_controller() {
+        let root = tree("multipath");
+        let (namespace, controller) = nvme(&root);
+
+        fs::create_dir_all(namespace.join("multipath")).unwrap();
+        symlink(controller.join("nvme0c0n1"), namespace.join("multipath/nvme0c0n1")).unwrap();
+
+        let path = owning_device(&namespace);
+
+        assert_eq!(path, DevicePath::from_lspci("0000:3d:00.0").ok());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn subsystems_link_to_their_controllers() {
+        let root = tree("subsystem");
+        let (namespace, controller) = nvme(&root);
+
+        symlink(&controller, namespace.join("../nvme0")).unwrap();
+
+        let path = owning_device(&namespace);
+
+        assert_eq!(path, DevicePath::from_lspci("0000:3d:00.0").ok());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn virtual_disks_have_no_device() {
+        let root = tree("virtual");
+        let disk = root.join("devices/virtual/block/loop0");
+
+        fs::create_dir_all(&disk).unwrap();
+
+        assert_eq!(owning_device(&disk), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn sizes_do_not_overflow() {
+        let root = tree("sizes");
+
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("size"), "1953525168\n").unwrap();
+        fs::write(root.join("start"), format!("{}\n", u64::MAX / 2)).unwrap();
+
+        assert_eq!(sectors(&root, "size"), Some(1_000_204_886_016));
+        assert_eq!(sectors(&root, "start"), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
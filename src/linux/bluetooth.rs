@@ -0,0 +1,326 @@
+use std::fs;
+
+use super::{
+    fixture,
+    sysfs,
+    DeviceLookup,
+};
+use crate::{
+    bluetooth::BluetoothController,
+    error::Error,
+};
+
+pub(super) const SYSFS_BLUETOOTH: &str = "/sys/class/bluetooth";
+pub(super) const DEBUGFS_BLUETOOTH: &str = "/sys/kernel/debug/bluetooth";
+
+/// The version information returned by Read Local Version Information.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct LocalVersion {
+    hci_version: u8,
+    hci_revision: u16,
+    manufacturer: u16,
+}
+
+/// Reads controllers through raw HCI sockets, which only Linux provides.
+#[cfg(target_os = "linux")]
+mod hci {
+    use std::{
+        mem,
+        os::fd::{
+            AsRawFd,
+            FromRawFd,
+            OwnedFd,
+        },
+        ptr,
+    };
+
+    const BTPROTO_HCI: libc::c_int = 1;
+    const SOL_HCI: libc::c_int = 0;
+    const HCI_FILTER: libc::c_int = 2;
+    const HCI_CHANNEL_RAW: u16 = 0;
+
+    /// `_IOR('H', 211, int)`
+    #[allow(clippy::cast_possible_wrap)]
+    const HCIGETDEVINFO: libc::Ioctl = 0x8004_48D3_u32 as libc::Ioctl;
+
+    const HCI_COMMAND_PKT: u8 = 0x01;
+    const HCI_EVENT_PKT: u8 = 0x04;
+    const EVT_CMD_COMPLETE: u8 = 0x0E;
+
+    /// Read Local Version Information, allowed on sockets without `CAP_NET_RAW`.
+    const OP_READ_LOCAL_VERSION: u16 = 0x1001;
+
+    /// How long to wait for a controller to answer a command, in milliseconds.
+    const COMMAND_TIMEOUT: libc::c_int = 1000;
+
+    /// `struct hci_dev_info`
+    #[repr(C)]
+    struct HciDevInfo {
+        dev_id: u16,
+        name: [u8; 8],
+        bdaddr: [u8; 6],
+        flags: u32,
+        kind: u8,
+        features: [u8; 8],
+        pkt_type: u32,
+        link_policy: u32,
+        link_mode: u32,
+        acl_mtu: u16,
+        acl_pkts: u16,
+        sco_mtu: u16,
+        sco_pkts: u16,
+        stat: [u32; 10],
+    }
+
+    /// `struct sockaddr_hci`
+    #[repr(C)]
+    struct SockaddrHci {
+        family: libc::sa_family_t,
+        dev: u16,
+        channel: u16,
+    }
+
+    /// `struct hci_filter`
+    #[repr(C)]
+    struct HciFilter {
+        type_mask: u32,
+        event_mask: [u32; 2],
+        opcode: u16,
+    }
+
+    /// Opens a raw HCI socket, which does not require any privileges.
+    fn hci_socket() -> Option<OwnedFd> {
+        // SAFETY: a successfully opened descriptor is owned by nothing else
+        unsafe {
+            let kind = libc::SOCK_RAW | libc::SOCK_CLOEXEC;
+            let fd = libc::socket(libc::AF_BLUETOOTH, kind, BTPROTO_HCI);
+
+            (fd >= 0).then(|| OwnedFd::from_raw_fd(fd))
+        }
+    }
+
+    /// Reads the public address of a controller, formatted most significant byte first.
+    pub(super) fn read_address(dev_id: u16) -> Option<String> {
+        let socket = hci_socket()?;
+
+        // SAFETY: `HciDevInfo` is plain old data, and `HCIGETDEVINFO` writes at most its size
+        let info = unsafe {
+            let mut info: HciDevInfo = mem::zeroed();
+            info.dev_id = dev_id;
+
+            if libc::ioctl(socket.as_raw_fd(), HCIGETDEVINFO, ptr::addr_of_mut!(info)) < 0 {
+                return None;
+            }
+
+            info
+        };
+
+        // Controllers that were never powered on report an empty address
+        if info.bdaddr == [0; 6] {
+            return None;
+        }
+
+        let bytes: Vec<String> = info.bdaddr.iter().rev().map(|b| format!("{b:02X}")).collect();
+
+        Some(bytes.join(":"))
+    }
+
+    /// Sends Read Local Version Information to a controller and waits for its answer.
+    ///
+    /// Controllers that are powered off cannot answer commands.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(super) fn read_local_version(dev_id: u16) -> Option<super::LocalVersion> {
+        let socket = hci_socket()?;
+        let fd = socket.as_raw_fd();
+
+        let address = SockaddrHci {
+            family: libc::AF_BLUETOOTH as libc::sa_family_t,
+            dev: dev_id,
+            channel: HCI_CHANNEL_RAW,
+        };
+
+        // Only let the completion of our command through
+        let filter = HciFilter {
+            type_mask: 1 << HCI_EVENT_PKT,
+            event_mask: [1 << EVT_CMD_COMPLETE, 0],
+            opcode: OP_READ_LOCAL_VERSION,
+        };
+
+        let [opcode_lo, opcode_hi] = OP_READ_LOCAL_VERSION.to_le_bytes();
+        let command = [HCI_COMMAND_PKT, opcode_lo, opcode_hi, 0];
+
+        let mut event = [0u8; 260];
+
+        // SAFETY: the pointers and lengths passed describe live values of the matching types
+        let length = unsafe {
+            let bound = libc::bind(
+                fd,
+                ptr::addr_of!(address).cast(),
+                mem::size_of::<SockaddrHci>() as libc::socklen_t,
+            );
+
+            let filtered = libc::setsockopt(
+                fd,
+                SOL_HCI,
+                HCI_FILTER,
+                ptr::addr_of!(filter).cast(),
+                mem::size_of::<HciFilter>() as libc::socklen_t,
+            );
+
+            if bound < 0 || filtered < 0 {
+                return None;
+            }
+
+            if libc::write(fd, command.as_ptr().cast(), command.len()) < 0 {
+                return None;
+            }
+
+            let mut poll = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            if libc::poll(&mut poll, 1, COMMAND_TIMEOUT) <= 0 {
+                return None;
+            }
+
+            libc::read(fd, event.as_mut_ptr().cast(), event.len())
+        };
+
+        let event = event.get(..usize::try_from(length).ok()?)?;
+
+        // Packet type, event code, length, command credits and opcode precede the return parameters
+        let parameters = event.strip_prefix(&[HCI_EVENT_PKT, EVT_CMD_COMPLETE])?.get(4..)?;
+
+        let [0, version, rev_lo, rev_hi, _, man_lo, man_hi, ..] = *parameters else {
+            return None;
+        };
+
+        Some(super::LocalVersion {
+            hci_version: version,
+            hci_revision: u16::from_le_bytes([rev_lo, rev_hi]),
+            manufacturer: u16::from_le_bytes([man_lo, man_hi]),
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn structs_match_kernel_layout() {
+            assert_eq!(mem::size_of::<HciDevInfo>(), 92);
+            assert_eq!(mem::size_of::<SockaddrHci>(), 6);
+            assert_eq!(mem::size_of::<HciFilter>(), 16);
+        }
+    }
+}
+
+/// Other platforms have no HCI sockets, so controllers are only read from debugfs.
+#[cfg(not(target_os = "linux"))]
+mod hci {
+    pub(super) fn read_address(_dev_id: u16) -> Option<String> {
+        None
+    }
+
+    pub(super) fn read_local_version(_dev_id: u16) -> Option<super::LocalVersion> {
+        None
+    }
+}
+
+/// The details of a controller that sysfs does not expose.
+pub(super) struct ControllerInfo {
+    address: Option<String>,
+    manufacturer: Option<u16>,
+    hci_version: Option<u8>,
+    hci_revision: Option<u16>,
+}
+
+impl ControllerInfo {
+    /// Reads the details of a controller through an HCI socket on Linux, falling back to debugfs.
+    ///
+    /// Sockets are not used while replaying a fixture, which stores the details as debugfs attributes.
+    pub(super) fn read(name: &str) -> Self {
+        let debug = fixture::system_path(DEBUGFS_BLUETOOTH).join(name);
+
+        let dev_id = name
+            .strip_prefix("hci")
+            .and_then(|i| i.parse().ok())
+            .filter(|_| !fixture::is_replaying());
+
+        let address = dev_id
+            .and_then(hci::read_address)
+            .or_else(|| {
+                sysfs::read_attr(&debug, "identity")
+                    .and_then(|i| i.split_whitespace().next().map(|a| a.to_owned()))
+            });
+
+        match dev_id.and_then(hci::read_local_version) {
+            Some(version) => Self {
+                address,
+                manufacturer: Some(version.manufacturer),
+                hci_version: Some(version.hci_version),
+                hci_revision: Some(version.hci_revision),
+            },
+            None => Self {
+                address,
+                manufacturer: sysfs::read_attr(&debug, "manufacturer").and_then(|m| m.parse().ok()),
+                hci_version: sysfs::read_attr(&debug, "hci_version").and_then(|v| v.parse().ok()),
+                hci_revision: sysfs::read_attr(&debug, "hci_revision").and_then(|r| r.parse().ok()),
+            },
+        }
+    }
+
+    /// Returns the details as the debugfs attributes they are read from while replaying a fixture.
+    pub(super) fn debugfs_attrs(&self) -> Vec<(&'static str, String)> {
+        [
+            ("identity", self.address.clone()),
+            ("manufacturer", self.manufacturer.map(|m| m.to_string())),
+            ("hci_version", self.hci_version.map(|v| v.to_string())),
+            ("hci_revision", self.hci_revision.map(|r| r.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect()
+    }
+}
+
+pub(crate) fn get_bluetooth_controllers() -> Result<Vec<BluetoothController>, Error> {
+    let Ok(entries) = fs::read_dir(fixture::system_path(SYSFS_BLUETOOTH)) else {
+        // The Bluetooth class only exists once the subsystem is loaded
+        return Ok(Vec::new());
+    };
+
+    let mut lookup = DeviceLookup::default();
+    let mut controllers = Vec::new();
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        // Connections are named `<controller>:<handle>`
+        if name.contains(':') {
+            continue;
+        }
+
+        let device = match sysfs::owning_device(&entry.path().join("device")) {
+            Some(path) => lookup.find(&path)?,
+            None => None,
+        };
+
+        let info = ControllerInfo::read(&name);
+
+        controllers.push(BluetoothController {
+            name,
+            device,
+            address: info.address,
+            manufacturer: info.manufacturer,
+            hci_version: info.hci_version,
+            hci_revision: info.hci_revision,
+        });
+    }
+
+    controllers.sort();
+
+    Ok(controllers)
+}
@@ -0,0 +1,105 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use super::{
+    fixture,
+    read_udev_data,
+    sysfs,
+};
+use crate::{
+    error::Error,
+    info::DeviceInfo,
+};
+
+/// Read the device tree `compatible` strings of a device, most specific first.
+fn compatible(dir: &Path) -> Vec<String> {
+    fs::read(dir.join("of_node/compatible"))
+        .map(|c| {
+            c.split(|b| *b == 0)
+                .filter(|s| !s.is_empty())
+                .map(|s| String::from_utf8_lossy(s).into_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn hex_id(dir: &Path, name: &str) -> Option<u16> {
+    let id = sysfs::read_attr(dir, name)?;
+
+    u16::from_str_radix(id.trim_start_matches("0x"), 16).ok()
+}
+
+/// Retrieve the devices on a sysfs bus, such as `platform`, `i2c`, `spi`, `sdio` or `thunderbolt`.
+///
+/// Buses that are not registered with the kernel have no devices.
+pub(crate) fn get_bus(bus: &str) -> Result<Vec<DeviceInfo>, Error> {
+    let buses = fixture::system_path(sysfs::SYSFS_BUS);
+
+    if !buses.exists() {
+        return Err(Error::CommandError);
+    }
+
+    let Ok(entries) = fs::read_dir(buses.join(bus).join("devices")) else {
+        return Ok(Vec::new());
+    };
+
+    let mut devices = Vec::new();
+
+    for entry in entries.flatten() {
+        let dir = entry.path();
+
+        let Some(path) = sysfs::device_path(&dir) else {
+            continue;
+        };
+
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let compatible = compatible(&dir);
+        let acpi_hid = sysfs::read_attr(&dir, "firmware_node/hid");
+        let udev = read_udev_data(&dir).unwrap_or_default();
+
+        // Identify the device by the names it reports, its most specific compatible string
+        // (`<vendor>,<model>`), its ACPI hardware id, or its name
+        let names =
+            sysfs::read_attr(&dir, "vendor_name").zip(sysfs::read_attr(&dir, "device_name"));
+
+        let (vendor, product) = if let Some(names) = names {
+            names
+        } else if let Some(c) = compatible.first() {
+            let (vendor, product) = c.split_once(',').unwrap_or(("", c));
+            (vendor.to_owned(), product.to_owned())
+        } else if let Some(hid) = &acpi_hid {
+            // ACPI ids are a 3 or 4 character vendor prefix followed by 4 hex digits
+            let vendor = hid.get(..hid.len().saturating_sub(4)).unwrap_or_default();
+            (vendor.to_owned(), hid.clone())
+        } else {
+            let product = sysfs::read_attr(&dir, "name").unwrap_or(name);
+            (String::new(), product)
+        };
+
+        devices.push(DeviceInfo {
+            path,
+            class: bus.to_owned(),
+            vendor,
+            product,
+            manufacturer: None,
+            class_id: hex_id(&dir, "class"),
+            vendor_id: hex_id(&dir, "vendor").unwrap_or_default(),
+            product_id: hex_id(&dir, "device").unwrap_or_default(),
+            manufacturer_id: None,
+            serial: None,
+            numa_node: None,
+            local_cpus: None,
+            resources: Vec::new(),
+            compatible,
+            acpi_hid,
+            udev_properties: udev.properties,
+            udev_tags: udev.tags,
+        });
+    }
+
+    devices.sort();
+
+    Ok(devices)
+}
@@ -0,0 +1,345 @@
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fs,
+    io,
+    os::unix,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+};
+
+use super::{
+    bluetooth,
+    sysfs,
+    udev,
+};
+use crate::error::Error;
+
+/// The buses enumerated by `Devices::all`, and the HD Audio bus read for sound cards.
+const BUSES: [&str; 8] = ["pci", "usb", "platform", "i2c", "spi", "sdio", "thunderbolt", "hdaudio"];
+
+/// The class directories read while listing block devices, network adapters and other views.
+const CLASSES: [&str; 7] = [
+    "/sys/block",
+    "/sys/class/net",
+    "/sys/class/tty",
+    "/sys/class/hidraw",
+    "/sys/class/input",
+    "/sys/class/sound",
+    "/sys/class/bluetooth",
+];
+
+/// Attributes read through links to firmware nodes, which are outside the device tree.
+const FIRMWARE_ATTRS: [&str; 2] = ["of_node/compatible", "firmware_node/hid"];
+
+/// Attributes larger than this are left out, in bytes.
+const MAX_ATTR_SIZE: u64 = 64 * 1024;
+
+/// The commands run while enumerating devices, with their arguments.
+const COMMANDS: [(&str, &[&str]); 2] = [("lspci", &["-mm", "-nn", "-D"]), ("lsusb", &["-v"])];
+
+const PROC_FILES: [&str; 5] = [
+    "/proc/sys/kernel/hostname",
+    "/proc/sys/kernel/osrelease",
+    "/proc/bus/input/devices",
+    "/proc/asound/cards",
+    "/proc/asound/pcm",
+];
+
+thread_local! {
+    static REPLAY_ROOT: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous replay root when dropped.
+struct ReplayGuard {
+    previous: Option<PathBuf>,
+}
+
+impl Drop for ReplayGuard {
+    fn drop(&mut self) {
+        REPLAY_ROOT.with_borrow_mut(|root| *root = self.previous.take());
+    }
+}
+
+/// Returns where an absolute path on the system is found, within the fixture being replayed if any.
+pub(crate) fn system_path(path: &str) -> PathBuf {
+    REPLAY_ROOT.with_borrow(|root| match root {
+        Some(root) => root.join(path.trim_start_matches('/')),
+        None => PathBuf::from(path),
+    })
+}
+
+/// Returns `true` if the calling thread is replaying a fixture.
+pub(crate) fn is_replaying() -> bool {
+    REPLAY_ROOT.with_borrow(Option::is_some)
+}
+
+/// Runs a command and returns its standard output, or the recorded output when replaying a fixture.
+pub(crate) fn command_output(program: &str, args: &[&str]) -> Result<Vec<u8>, Error> {
+    let recorded = REPLAY_ROOT.with_borrow(|root| {
+        root.as_ref()
+            .map(|r| r.join("commands").join(program))
+    });
+
+    match recorded {
+        Some(file) => fs::read(file).map_err(|_| Error::CommandError),
+        None => Command::new(program)
+            .args(args)
+            .output()
+            .map(|o| o.stdout)
+            .map_err(|_| Error::CommandError),
+    }
+}
+
+/// Runs a function with the Linux backend reading from a fixture instead of the system.
+pub(crate) fn replay_fixture<T>(root: &Path, f: impl FnOnce() -> T) -> T {
+    let previous = REPLAY_ROOT.with_borrow_mut(|r| r.replace(root.to_owned()));
+    let _guard = ReplayGuard { previous };
+
+    f()
+}
+
+fn io_error(e: &io::Error) -> Error {
+    match e.kind() {
+        io::ErrorKind::NotFound => Error::NotFound,
+        _ => Error::CommandError,
+    }
+}
+
+/// Returns `true` for attributes that map device memory or read from the device, such as
+/// `resource0`, `rom` and `vpd`, which are left out.
+fn is_skipped_attr(name: &str) -> bool {
+    name == "rom" || name == "vpd" || (name.starts_with("resource") && name != "resource")
+}
+
+/// Copies the parts of the system the Linux backend reads into fixture directories.
+struct Recorder {
+    root: PathBuf,
+    recorded: HashSet<PathBuf>,
+}
+
+impl Recorder {
+    /// Returns where an absolute path on the system is stored in the fixture.
+    fn target(&self, path: &Path) -> PathBuf {
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<(), Error> {
+        fs::create_dir_all(self.target(path)).map_err(|e| io_error(&e))
+    }
+
+    fn write_file(&self, path: &Path, contents: &[u8]) -> Result<(), Error> {
+        let target = self.target(path);
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error(&e))?;
+        }
+
+        fs::write(target, contents).map_err(|e| io_error(&e))
+    }
+
+    fn copy_file(&self, path: &Path) -> Result<(), Error> {
+        if fs::metadata(path).is_ok_and(|m| m.len() > MAX_ATTR_SIZE) {
+            return Ok(());
+        }
+
+        // Attributes that cannot be read, such as write-only ones, are left out
+        let Ok(contents) = fs::read(path) else {
+            return Ok(());
+        };
+
+        self.write_file(path, &contents)
+    }
+
+    fn copy_link(&self, path: &Path) -> Result<(), Error> {
+        let Ok(link) = fs::read_link(path) else {
+            return Ok(());
+        };
+
+        let target = self.target(path);
+
+        if target.symlink_metadata().is_ok() {
+            return Ok(());
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error(&e))?;
+        }
+
+        unix::fs::symlink(link, target).map_err(|e| io_error(&e))
+    }
+
+    /// Records the attributes and links of a directory, without descending into it.
+    fn record_dir(&mut self, dir: &Path) -> Result<(), Error> {
+        if !self.recorded.insert(dir.to_owned()) {
+            return Ok(());
+        }
+
+        self.create_dir(dir)?;
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(());
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            let Ok(kind) = entry.file_type() else {
+                continue;
+            };
+
+            if kind.is_symlink() {
+                if name == "of_node" || name == "firmware_node" {
+                    continue;
+                }
+
+                self.copy_link(&path)?;
+
+                // Device links name the ports a Thunderbolt controller tunnels through
+                if name.starts_with("consumer:") || name.starts_with("supplier:") {
+                    if let Ok(link) = fs::canonicalize(&path) {
+                        self.record_dir(&link)?;
+                    }
+                }
+            } else if kind.is_file() && !is_skipped_attr(&name) {
+                self.copy_file(&path)?;
+            }
+        }
+
+        for attr in FIRMWARE_ATTRS {
+            self.copy_file(&dir.join(attr))?;
+        }
+
+        if let Some(id) = udev::udev_data_id(dir) {
+            self.copy_file(&Path::new(udev::UDEV_DATA_DIR).join(id))?;
+        }
+
+        Ok(())
+    }
+
+    /// Records a device directory with everything below it up to other devices, such as its
+    /// interfaces and class devices, and the directories above it up to `/sys/devices`.
+    fn record_device(&mut self, dir: &Path) -> Result<(), Error> {
+        let mut children = Vec::new();
+
+        sysfs::walk_children(dir, &mut |child| children.push(child.to_owned()));
+
+        for child in children {
+            self.record_dir(&child)?;
+        }
+
+        for dir in dir.ancestors().take_while(|d| d.file_name().is_some_and(|n| n != "devices")) {
+            self.record_dir(dir)?;
+        }
+
+        Ok(())
+    }
+
+    /// Records the links of a bus or class directory, and the devices they point to.
+    fn record_links(&mut self, links: &Path) -> Result<(), Error> {
+        let Ok(entries) = fs::read_dir(links) else {
+            return Ok(());
+        };
+
+        self.create_dir(links)?;
+
+        for entry in entries.flatten() {
+            let link = entry.path();
+
+            if let Ok(dir) = fs::canonicalize(&link) {
+                self.record_device(&dir)?;
+            }
+
+            self.copy_link(&link)?;
+        }
+
+        Ok(())
+    }
+
+    /// Records the controller details read through HCI sockets as the debugfs attributes they
+    /// are read from while replaying.
+    fn record_bluetooth(&self) -> Result<(), Error> {
+        let Ok(entries) = fs::read_dir(bluetooth::SYSFS_BLUETOOTH) else {
+            return Ok(());
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            // Connections are named `<controller>:<handle>`
+            if name.contains(':') {
+                continue;
+            }
+
+            let debug = Path::new(bluetooth::DEBUGFS_BLUETOOTH).join(&name);
+
+            for (attr, value) in bluetooth::ControllerInfo::read(&name).debugfs_attrs() {
+                self.write_file(&debug.join(attr), format!("{value}\n").as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record_commands(&self) -> Result<(), Error> {
+        let commands = self.root.join("commands");
+
+        fs::create_dir_all(&commands).map_err(|e| io_error(&e))?;
+
+        for (program, args) in COMMANDS {
+            // Commands that are not installed are left out, and fail the same way when replaying
+            if let Ok(output) = command_output(program, args) {
+                fs::write(commands.join(program), output).map_err(|e| io_error(&e))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn record_fixture(root: &Path) -> Result<(), Error> {
+    let mut recorder = Recorder {
+        root: root.to_owned(),
+        recorded: HashSet::new(),
+    };
+
+    fs::create_dir_all(root).map_err(|e| io_error(&e))?;
+
+    recorder.record_commands()?;
+
+    for file in PROC_FILES {
+        recorder.copy_file(Path::new(file))?;
+    }
+
+    // Codecs are only listed here on kernels without the HD Audio bus
+    for card in fs::read_dir("/proc/asound").into_iter().flatten().flatten() {
+        // Cards are also linked by their ids, such as `PCH`
+        if !card.file_name().to_string_lossy().starts_with("card") {
+            continue;
+        }
+
+        for codec in fs::read_dir(card.path()).into_iter().flatten().flatten() {
+            if codec.file_name().to_string_lossy().starts_with("codec#") {
+                recorder.copy_file(&codec.path())?;
+            }
+        }
+    }
+
+    if Path::new(sysfs::SYSFS_BUS).exists() {
+        recorder.create_dir(Path::new(sysfs::SYSFS_BUS))?;
+    }
+
+    for bus in BUSES {
+        recorder.record_links(&Path::new(sysfs::SYSFS_BUS).join(bus).join("devices"))?;
+    }
+
+    for class in CLASSES {
+        recorder.record_links(Path::new(class))?;
+    }
+
+    recorder.record_bluetooth()
+}
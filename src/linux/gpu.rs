@@ -0,0 +1,103 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use super::{
+    get_pci,
+    sysfs,
+};
+use crate::{
+    edid::Edid,
+    error::Error,
+    gpu::{
+        Connector,
+        ConnectorStatus,
+        DisplayAdapter,
+    },
+};
+
+// PCI base class of display controllers
+const PCI_CLASS_DISPLAY: u16 = 0x03;
+
+/// Connectors are named `<card>-<connector>`, such as `card0-HDMI-A-1`.
+fn connector(dir: &Path, card: &str) -> Option<Connector> {
+    let name = dir
+        .file_name()?
+        .to_str()?
+        .strip_prefix(card)?
+        .strip_prefix('-')?
+        .to_owned();
+
+    let status = match sysfs::read_attr(dir, "status").as_deref() {
+        Some("connected") => ConnectorStatus::Connected,
+        Some("disconnected") => ConnectorStatus::Disconnected,
+        _ => ConnectorStatus::Unknown,
+    };
+
+    let modes = sysfs::read_attr(dir, "modes")
+        .unwrap_or_default()
+        .lines()
+        .map(|m| m.to_owned())
+        .collect();
+
+    let edid = fs::read(dir.join("edid")).ok().filter(|e| !e.is_empty());
+    let monitor = edid.as_deref().and_then(|e| Edid::parse(e).ok());
+
+    Some(Connector {
+        name,
+        status,
+        enabled: sysfs::read_attr(dir, "enabled").as_deref() == Some("enabled"),
+        modes,
+        edid,
+        monitor,
+    })
+}
+
+pub(crate) fn get_display_adapters() -> Result<Vec<DisplayAdapter>, Error> {
+    let mut adapters = Vec::new();
+
+    for device in get_pci()? {
+        if device.class_id.map(|c| c >> 8) != Some(PCI_CLASS_DISPLAY) {
+            continue;
+        }
+
+        let Some(dir) = sysfs::device_dir(&device.path) else {
+            continue;
+        };
+
+        let mut adapter = DisplayAdapter {
+            boot_vga: sysfs::read_attr(&dir, "boot_vga").as_deref() == Some("1"),
+            device,
+            cards: Vec::new(),
+            render_nodes: Vec::new(),
+            connectors: Vec::new(),
+        };
+
+        let entries = fs::read_dir(dir.join("drm")).into_iter().flatten().flatten();
+
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            if name.starts_with("renderD") {
+                adapter.render_nodes.push(format!("/dev/dri/{name}"));
+            } else if name.starts_with("card") {
+                let connectors = fs::read_dir(entry.path()).into_iter().flatten().flatten();
+
+                adapter
+                    .connectors
+                    .extend(connectors.filter_map(|c| connector(&c.path(), &name)));
+
+                adapter.cards.push(format!("/dev/dri/{name}"));
+            }
+        }
+
+        adapter.cards.sort();
+        adapter.render_nodes.sort();
+        adapter.connectors.sort();
+
+        adapters.push(adapter);
+    }
+
+    Ok(adapters)
+}
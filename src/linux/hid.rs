@@ -0,0 +1,79 @@
+use std::fs;
+
+use super::{
+    fixture,
+    sysfs,
+    DeviceLookup,
+};
+use crate::{
+    error::Error,
+    hid::{
+        HidDevice,
+        ReportDescriptor,
+    },
+};
+
+const SYSFS_HIDRAW: &str = "/sys/class/hidraw";
+
+/// `BUS_BLUETOOTH`, see `input.h`
+const BUS_BLUETOOTH: u16 = 0x05;
+
+/// Parse `HID_ID`, formatted as `<bus>:<vendor>:<product>` with 32-bit hex ids.
+fn parse_hid_id(id: &str) -> Option<(u16, u16, u16)> {
+    let mut fields = id.split(':').map(|f| u32::from_str_radix(f, 16).ok());
+
+    let bus_type = u16::try_from(fields.next()??).ok()?;
+    let vendor_id = u16::try_from(fields.next()??).ok()?;
+    let product_id = u16::try_from(fields.next()??).ok()?;
+
+    Some((bus_type, vendor_id, product_id))
+}
+
+pub(crate) fn get_hid_devices() -> Result<Vec<HidDevice>, Error> {
+    let Ok(entries) = fs::read_dir(fixture::system_path(SYSFS_HIDRAW)) else {
+        // The hidraw class only exists once a HID device is present
+        return Ok(Vec::new());
+    };
+
+    let mut lookup = DeviceLookup::default();
+    let mut devices = Vec::new();
+
+    for entry in entries.flatten() {
+        let hid = entry.path().join("device");
+        let uevent = sysfs::read_uevent(&hid);
+
+        // Devices with an unreadable id or descriptor are skipped rather than failing the list
+        let Some((bus_type, vendor_id, product_id)) =
+            uevent.get("HID_ID").and_then(|id| parse_hid_id(id))
+        else {
+            continue;
+        };
+
+        let Some(report_descriptor) = fs::read(hid.join("report_descriptor"))
+            .ok()
+            .and_then(|d| ReportDescriptor::parse(&d).ok())
+        else {
+            continue;
+        };
+
+        // Bluetooth devices sit below their controller, which is not the device they belong to
+        let device = match sysfs::owning_device(&hid) {
+            Some(path) if bus_type != BUS_BLUETOOTH => lookup.find(&path)?,
+            _ => None,
+        };
+
+        devices.push(HidDevice {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            device,
+            hid_name: uevent.get("HID_NAME").cloned(),
+            bus_type,
+            vendor_id,
+            product_id,
+            report_descriptor,
+        });
+    }
+
+    devices.sort();
+
+    Ok(devices)
+}
@@ -0,0 +1,20 @@
+use super::{
+    fixture,
+    sysfs,
+};
+use crate::snapshot::HostInfo;
+
+const PROC_KERNEL: &str = "/proc/sys/kernel";
+
+pub(crate) fn get_host() -> HostInfo {
+    let dir = fixture::system_path(PROC_KERNEL);
+    let dir = dir.as_path();
+
+    HostInfo {
+        hostname: sysfs::read_attr(dir, "hostname"),
+        os: Some(std::env::consts::OS.to_owned()),
+        kernel: sysfs::read_attr(dir, "osrelease"),
+        arch: Some(std::env::consts::ARCH.to_owned()),
+        crate_version: Some(env!("CARGO_PKG_VERSION").to_owned()),
+    }
+}
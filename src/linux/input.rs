@@ -0,0 +1,258 @@
+use std::fs;
+
+use super::{
+    fixture,
+    sysfs,
+    DeviceLookup,
+};
+use crate::{
+    error::Error,
+    input::{
+        InputCapabilities,
+        InputDevice,
+    },
+};
+
+const PROC_INPUT_DEVICES: &str = "/proc/bus/input/devices";
+const SYSFS: &str = "/sys";
+
+/// Decode a capability bitmask, written as hex words with the most significant word first.
+fn parse_bitmask(mask: &str) -> Option<Vec<u16>> {
+    let mut bits = Vec::new();
+
+    for (word_index, word) in mask.split_whitespace().rev().enumerate() {
+        let word = u64::from_str_radix(word, 16).ok()?;
+
+        for bit in 0..usize::BITS as usize {
+            if word & (1 << bit) != 0 {
+                bits.push(u16::try_from(word_index * usize::BITS as usize + bit).ok()?);
+            }
+        }
+    }
+
+    Some(bits)
+}
+
+/// Parse a `Key=Value` field, which may be quoted.
+fn field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let start = line.find(&format!("{key}="))? + key.len() + 1;
+    let value = &line[start..];
+
+    if let Some(quoted) = value.strip_prefix('"') {
+        quoted.split_once('"').map(|(v, _)| v)
+    } else {
+        value.split_whitespace().next().or(Some(""))
+    }
+}
+
+fn id_field(line: &str, key: &str) -> Option<u16> {
+    field(line, key).and_then(|v| u16::from_str_radix(v, 16).ok())
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value.filter(|v| !v.is_empty()).map(|v| v.to_owned())
+}
+
+/// Parse a block of `/proc/bus/input/devices`, returning the device and its sysfs path.
+///
+/// Malformed lines are skipped.
+fn parse_device(block: &str) -> (InputDevice, Option<&str>) {
+    let mut device = InputDevice {
+        name: String::new(),
+        device: None,
+        input_name: None,
+        phys: None,
+        uniq: None,
+        bus_type: 0,
+        vendor_id: 0,
+        product_id: 0,
+        version: 0,
+        handlers: Vec::new(),
+        capabilities: InputCapabilities::default(),
+    };
+
+    let mut sysfs_path = None;
+
+    for line in block.lines() {
+        let Some((kind, rest)) = line.split_once(": ") else {
+            continue;
+        };
+
+        match kind {
+            "I" => {
+                let ids = ["Bus", "Vendor", "Product", "Version"].map(|k| id_field(rest, k));
+
+                if let [Some(bus_type), Some(vendor_id), Some(product_id), Some(version)] = ids {
+                    device.bus_type = bus_type;
+                    device.vendor_id = vendor_id;
+                    device.product_id = product_id;
+                    device.version = version;
+                }
+            }
+            "N" => device.input_name = non_empty(field(rest, "Name")),
+            "P" => device.phys = non_empty(field(rest, "Phys")),
+            "U" => device.uniq = non_empty(field(rest, "Uniq")),
+            "H" => {
+                let handlers = rest.trim_start_matches("Handlers=");
+                device.handlers = handlers.split_whitespace().map(|h| h.to_owned()).collect();
+            }
+            "S" => {
+                let Some(path) = field(rest, "Sysfs").filter(|p| !p.is_empty()) else {
+                    continue;
+                };
+
+                path.rsplit('/').next().unwrap_or_default().clone_into(&mut device.name);
+                sysfs_path = Some(path);
+            }
+            "B" => {
+                let Some((bitmap, bits)) = rest
+                    .split_once('=')
+                    .and_then(|(b, mask)| Some((b, parse_bitmask(mask)?)))
+                else {
+                    continue;
+                };
+
+                let caps = &mut device.capabilities;
+
+                match bitmap {
+                    "PROP" => caps.properties = bits,
+                    "EV" => caps.events = bits,
+                    "KEY" => caps.keys = bits,
+                    "REL" => caps.relative = bits,
+                    "ABS" => caps.absolute = bits,
+                    "MSC" => caps.misc = bits,
+                    "LED" => caps.leds = bits,
+                    "SW" => caps.switches = bits,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (device, sysfs_path)
+}
+
+pub(crate) fn get_input_devices() -> Result<Vec<InputDevice>, Error> {
+    let output = fs::read_to_string(fixture::system_path(PROC_INPUT_DEVICES))
+        .map_err(|_| Error::CommandError)?;
+
+    let mut lookup = DeviceLookup::default();
+    let mut devices = Vec::new();
+
+    for block in output.split("\n\n").filter(|b| !b.trim().is_empty()) {
+        let (mut device, sysfs_path) = parse_device(block);
+
+        let dir = sysfs_path.map(|p| fixture::system_path(SYSFS).join(p.trim_start_matches('/')));
+
+        if let Some(path) = dir.and_then(|d| sysfs::owning_device(&d)) {
+            device.device = lookup.find(&path)?;
+        }
+
+        devices.push(device);
+    }
+
+    devices.sort();
+
+    Ok(devices)
+}
+
+// The samples were written by a 64-bit kernel, which prints masks in 64-bit words
+#[cfg(all(test, target_pointer_width = "64"))]
+mod tests {
+    use super::*;
+
+    const KEYBOARD: &str = "\
+I: Bus=0011 Vendor=0001 Product=0001 Version=ab41
+N: Name=\"AT Translated Set 2 keyboard\"
+P: Phys=isa0060/serio0/input0
+S: Sysfs=/devices/platform/i8042/serio0/input/input3
+U: Uniq=
+H: Handlers=sysrq kbd event3 leds 
+B: PROP=0
+B: EV=120013
+B: KEY=402000000 3803078f800d001 feffffdfffefffff fffffffffffffffe
+B: MSC=10
+B: LED=7
+";
+
+    const MOUSE: &str = "\
+I: Bus=0003 Vendor=046d Product=c52b Version=0111
+N: Name=\"Logitech USB Receiver\"
+P: Phys=usb-0000:00:14.0-2/input0
+S: Sysfs=/devices/pci0000:00/0000:00:14.0/usb1/1-2/1-2:1.0/0003:046D:C52B.0001/input/input5
+U: Uniq=
+H: Handlers=mouse0 event5 
+B: PROP=0
+B: EV=17
+B: KEY=1f0000 0 0 0 0
+B: REL=1943
+B: MSC=10
+";
+
+    #[test]
+    fn decodes_bitmasks() {
+        assert_eq!(parse_bitmask("0"), Some(vec![]));
+        assert_eq!(parse_bitmask("120013"), Some(vec![0, 1, 4, 17, 20]));
+        assert_eq!(parse_bitmask("1943"), Some(vec![0, 1, 6, 8, 11, 12]));
+        assert_eq!(parse_bitmask("1 0"), Some(vec![64]));
+        assert_eq!(parse_bitmask("zz"), None);
+    }
+
+    #[test]
+    fn decodes_multi_word_key_masks() {
+        let keys = parse_bitmask("402000000 3803078f800d001 feffffdfffefffff fffffffffffffffe")
+            .unwrap();
+
+        // KEY_ESC to KEY_F5, but not KEY_RESERVED
+        assert!(!keys.contains(&0));
+        assert!((1..64).all(|k| keys.contains(&k)));
+        // KEY_MACRO in the second word from the end, and the bits of the first word
+        assert!(keys.contains(&(64 + 48)));
+        assert!(keys.contains(&(3 * 64 + 25)));
+        assert_eq!(keys.last(), Some(&(3 * 64 + 34)));
+
+        let buttons = parse_bitmask("1f0000 0 0 0 0").unwrap();
+
+        // BTN_LEFT to BTN_EXTRA
+        assert_eq!(buttons, [0x110, 0x111, 0x112, 0x113, 0x114]);
+    }
+
+    #[test]
+    fn parses_keyboard() {
+        let (device, sysfs_path) = parse_device(KEYBOARD);
+
+        assert_eq!(sysfs_path, Some("/devices/platform/i8042/serio0/input/input3"));
+        assert_eq!(device.name(), "input3");
+        assert_eq!(device.input_name(), Some("AT Translated Set 2 keyboard"));
+        assert_eq!(device.phys(), Some("isa0060/serio0/input0"));
+        assert_eq!(device.uniq(), None);
+        assert_eq!(device.bus_type(), 0x11);
+        assert_eq!((device.vendor_id(), device.product_id(), device.version()), (1, 1, 0xAB41));
+        assert_eq!(device.handlers(), ["sysrq", "kbd", "event3", "leds"]);
+        assert_eq!(device.capabilities().leds(), [0, 1, 2]);
+        assert!(device.is_keyboard());
+        assert!(!device.is_mouse());
+    }
+
+    #[test]
+    fn parses_mouse() {
+        let (device, _) = parse_device(MOUSE);
+
+        assert_eq!(device.name(), "input5");
+        assert_eq!((device.vendor_id(), device.product_id()), (0x046D, 0xC52B));
+        assert_eq!(device.capabilities().events(), [0, 1, 2, 4]);
+        assert_eq!(device.capabilities().relative(), [0, 1, 6, 8, 11, 12]);
+        assert!(device.is_mouse());
+        assert!(!device.is_keyboard());
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let block = format!("{MOUSE}garbage\nB: KEY=zz\nB: LED\nS: Sysfs=\nI: Bus=usb\n");
+        let (device, sysfs_path) = parse_device(&block);
+
+        assert_eq!(device, parse_device(MOUSE).0);
+        assert!(sysfs_path.is_some());
+    }
+}
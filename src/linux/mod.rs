@@ -0,0 +1,392 @@
+mod block;
+mod bluetooth;
+mod bus;
+mod fixture;
+mod gpu;
+mod hid;
+mod host;
+mod input;
+mod net;
+mod node;
+mod power;
+mod serial;
+mod sound;
+mod sysfs;
+mod thunderbolt;
+mod udev;
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+pub(crate) use self::block::get_block_devices;
+pub(crate) use self::bluetooth::get_bluetooth_controllers;
+pub(crate) use self::bus::get_bus;
+pub(crate) use self::fixture::{
+    record_fixture,
+    replay_fixture,
+};
+pub(crate) use self::gpu::get_display_adapters;
+pub(crate) use self::hid::get_hid_devices;
+pub(crate) use self::host::get_host;
+pub(crate) use self::input::get_input_devices;
+pub(crate) use self::net::get_network_adapters;
+pub(crate) use self::node::get_nodes;
+pub(crate) use self::power::{
+    get_power_state,
+    set_autosuspend_delay,
+    set_power_control,
+};
+pub(crate) use self::serial::get_serial_ports;
+pub(crate) use self::sound::get_sound_cards;
+pub(crate) use self::thunderbolt::get_thunderbolt_domains;
+pub(crate) use self::udev::{
+    evaluate_udev_rules,
+    read_udev_data,
+};
+use crate::{
+    error::Error,
+    info::DeviceInfo,
+    path::DevicePath,
+    resource::PCI_ROM_RESOURCE,
+};
+
+#[rustfmt::skip]
+lazy_static! {
+    static ref LSPCI_REGEX: Regex = Regex::new("(^.*? )|(\".*?\")").unwrap();
+    static ref LSUSB_PATH_REGEX: Regex = Regex::new("Bus [0-9]+ Device [0-9]+").unwrap();
+    static ref LSUSB_CLASS_REGEX: Regex = Regex::new("\\s*?bDeviceClass\\s*?.*?\\n").unwrap();
+    static ref LSUSB_VENDOR_REGEX: Regex = Regex::new("\\s*?idVendor\\s*?.*?\\n").unwrap();
+    static ref LSUSB_PRODUCT_REGEX: Regex = Regex::new("\\s*?iProduct\\s*?.*?\\n").unwrap();
+    static ref LSUSB_PRODUCT_ID_REGEX: Regex = Regex::new("\\s*?idProduct\\s*?.*?\\n").unwrap();
+    static ref LSUSB_MANUFACTURER_REGEX: Regex = Regex::new("\\s*?iManufacturer\\s*?.*?\\n").unwrap();
+}
+
+trait RSplitAt {
+    fn rsplit_at(&self, mid: usize) -> (&str, &str);
+}
+
+impl RSplitAt for &str {
+    fn rsplit_at(&self, mid: usize) -> (&str, &str) {
+        self.split_at(self.len() - mid)
+    }
+}
+
+fn id_from_raw(raw: &str) -> Result<u16, Error> {
+    let trimmed = raw.trim().trim_matches('[').trim_matches(']');
+
+    u16::from_str_radix(trimmed, 16).map_err(|_| Error::ParseError)
+}
+
+// Empty output is reported as a parse error, as it always has been
+#[allow(clippy::str_split_at_newline)]
+pub(crate) fn get_pci() -> Result<Vec<DeviceInfo>, Error> {
+    // `-D` prints the domain of every device, which is not always `0000` on multi-socket servers
+    let output = fixture::command_output("lspci", &["-mm", "-nn", "-D"])?;
+
+    let output_str = String::from_utf8(output).map_err(|_| Error::ParseError)?;
+
+    let mut devices = Vec::new();
+
+    for line in output_str.trim().split('\n') {
+        let mut matches = Vec::new();
+
+        for m in LSPCI_REGEX.find_iter(line) {
+            matches.push(m.as_str().trim().trim_matches('"'));
+        }
+
+        let address = matches.first().ok_or(Error::ParseError)?;
+        let path = DevicePath::from_lspci(address).map_err(|_| Error::ParseError)?;
+
+        let (class, class_id) = matches.get(1).ok_or(Error::ParseError)?.rsplit_at(7);
+        let class_id = id_from_raw(class_id)?;
+
+        let (vendor, vendor_id) = matches.get(2).ok_or(Error::ParseError)?.rsplit_at(7);
+        let vendor_id = id_from_raw(vendor_id)?;
+
+        let (product, product_id) = matches.get(3).ok_or(Error::ParseError)?.rsplit_at(7);
+        let product_id = id_from_raw(product_id)?;
+
+        // NUMA node, local CPUs
+
+        let dir = sysfs::device_dir(&path);
+
+        let numa_node = dir
+            .as_deref()
+            .and_then(|d| sysfs::read_attr(d, "numa_node"))
+            .and_then(|n| n.parse::<u16>().ok());
+
+        let local_cpus = dir
+            .as_deref()
+            .and_then(|d| sysfs::read_attr(d, "local_cpulist"))
+            .and_then(|l| sysfs::parse_cpulist(&l));
+
+        // Resources
+
+        let resources = dir
+            .as_deref()
+            .and_then(|d| sysfs::read_attr(d, "resource"))
+            .map(|r| sysfs::parse_resources(&r))
+            .unwrap_or_default();
+
+        // udev properties
+
+        let udev = dir
+            .as_deref()
+            .and_then(|d| read_udev_data(d).ok())
+            .unwrap_or_default();
+
+        devices.push(DeviceInfo {
+            path,
+            class: class.to_owned(),
+            vendor: vendor.to_owned(),
+            product: product.to_owned(),
+            manufacturer: None,
+            class_id: Some(class_id),
+            vendor_id,
+            product_id,
+            manufacturer_id: None,
+            serial: None,
+            numa_node,
+            local_cpus,
+            resources,
+            compatible: Vec::new(),
+            acpi_hid: None,
+            udev_properties: udev.properties,
+            udev_tags: udev.tags,
+        });
+    }
+
+    Ok(devices)
+}
+
+/// Lazily enumerates devices to resolve paths found in sysfs.
+#[derive(Default)]
+pub(crate) struct DeviceLookup {
+    buses: HashMap<&'static str, Vec<DeviceInfo>>,
+}
+
+impl DeviceLookup {
+    pub(crate) fn find(&mut self, path: &DevicePath) -> Result<Option<DeviceInfo>, Error> {
+        let bus = path.bus();
+
+        if !self.buses.contains_key(bus) {
+            let devices = match path {
+                DevicePath::PCI { .. } => get_pci()?,
+                DevicePath::USB { .. } => get_usb()?,
+                _ => get_bus(bus)?,
+            };
+
+            self.buses.insert(bus, devices);
+        }
+
+        Ok(self.buses[bus].iter().find(|d| d.path == *path).cloned())
+    }
+}
+
+pub(crate) fn get_sysfs_dir(path: &DevicePath) -> Result<PathBuf, Error> {
+    sysfs::device_dir(path).ok_or(Error::NotFound)
+}
+
+pub(crate) fn get_sysfs_owner(dir: &Path) -> Result<DevicePath, Error> {
+    sysfs::owning_device(dir).ok_or(Error::NotFound)
+}
+
+pub(crate) fn get_usb_path(bus: u8, device: u8) -> Result<DevicePath, Error> {
+    let dir = sysfs::usb_device_dir(bus, device).ok_or(Error::NotFound)?;
+
+    sysfs::device_path(&dir).ok_or(Error::NotFound)
+}
+
+pub(crate) fn get_usb_address(path: &DevicePath) -> Result<(u8, u8), Error> {
+    let DevicePath::USB { .. } = path else {
+        return Err(Error::NotFound);
+    };
+
+    let dir = sysfs::device_dir(path).ok_or(Error::NotFound)?;
+
+    let number = |name| {
+        sysfs::read_attr(&dir, name)
+            .and_then(|n| n.parse::<u8>().ok())
+            .ok_or(Error::ParseError)
+    };
+
+    Ok((number("busnum")?, number("devnum")?))
+}
+
+pub(crate) fn get_parent(path: &DevicePath) -> Result<Option<DevicePath>, Error> {
+    let dir = sysfs::device_dir(path).ok_or(Error::NotFound)?;
+    let dir = fs::canonicalize(dir).map_err(|_| Error::NotFound)?;
+
+    Ok(dir.parent().and_then(sysfs::owning_device))
+}
+
+pub(crate) fn get_pci_resource_path(path: &DevicePath, index: u8) -> Result<PathBuf, Error> {
+    let dir = sysfs::device_dir(path).ok_or(Error::NotFound)?;
+
+    let file = if index == PCI_ROM_RESOURCE {
+        dir.join("rom")
+    } else {
+        dir.join(format!("resource{index}"))
+    };
+
+    if file.exists() {
+        Ok(file)
+    } else {
+        Err(Error::NotFound)
+    }
+}
+
+pub(crate) fn get_usb() -> Result<Vec<DeviceInfo>, Error> {
+    let output = fixture::command_output("lsusb", &["-v"])?;
+
+    let output_str = String::from_utf8(output).map_err(|_| Error::ParseError)?;
+
+    let mut devices = Vec::new();
+
+    for dev in output_str.split("\n\n") {
+        // Path
+
+        let path_line = LSUSB_PATH_REGEX.find(dev).ok_or(Error::ParseError)?;
+
+        let mut path = path_line.as_str().split(' ');
+
+        let _ = path.next().ok_or(Error::ParseError)?;
+        let bus = path.next().ok_or(Error::ParseError)?;
+        let _ = path.next().ok_or(Error::ParseError)?;
+        let device = path.next().ok_or(Error::ParseError)?;
+
+        let bus = bus.parse::<u8>().map_err(|_| Error::ParseError)?;
+        let device = device.parse::<u8>().map_err(|_| Error::ParseError)?;
+
+        // Class
+
+        let class_line = LSUSB_CLASS_REGEX.find(dev).ok_or(Error::ParseError)?;
+
+        let class_line = class_line
+            .as_str()
+            .trim()
+            .trim_start_matches("bDeviceClass")
+            .trim()
+            .trim_start_matches("0x");
+
+        let (class_id, class) = class_line.split_once(' ').unwrap_or((class_line, "Other"));
+
+        let class_id = class_id.parse::<u16>().map_err(|_| Error::ParseError)?;
+
+        // Vendor
+
+        let vendor_line = LSUSB_VENDOR_REGEX.find(dev).ok_or(Error::ParseError)?;
+
+        let (vendor_id, vendor) = vendor_line
+            .as_str()
+            .trim()
+            .trim_start_matches("idVendor")
+            .trim()
+            .trim_start_matches("0x")
+            .split_at(4);
+
+        let vendor_id = u16::from_str_radix(vendor_id, 16).map_err(|_| Error::ParseError)?;
+        let vendor = vendor.trim();
+
+        // Product
+
+        let product_line_a = LSUSB_PRODUCT_REGEX.find(dev).ok_or(Error::ParseError)?;
+
+        let (_, product_a) = product_line_a
+            .as_str()
+            .trim()
+            .trim_start_matches("iProduct")
+            .trim()
+            .split_once(' ')
+            .unwrap_or(("", ""));
+
+        let product_line_b = LSUSB_PRODUCT_ID_REGEX.find(dev).ok_or(Error::ParseError)?;
+
+        let (product_id, product_b) = product_line_b
+            .as_str()
+            .trim()
+            .trim_start_matches("idProduct")
+            .trim()
+            .trim_start_matches("0x")
+            .split_at(4);
+
+        let product_b = product_b.trim();
+        let product_id = u16::from_str_radix(product_id, 16).map_err(|_| Error::ParseError)?;
+
+        let product = format!("{product_a} {product_b}");
+        let product = product.trim();
+
+        // Manufacturer
+
+        let manufacturer_line = LSUSB_MANUFACTURER_REGEX
+            .find(dev)
+            .ok_or(Error::ParseError)?;
+
+        let manufacturer_result = manufacturer_line
+            .as_str()
+            .trim()
+            .trim_start_matches("iManufacturer")
+            .trim()
+            .split_once(' ');
+
+        let mut manufacturer = None;
+        let mut manufacturer_id = None;
+
+        if let Some((man_id, man)) = manufacturer_result {
+            let man_id = man_id.parse::<u16>().map_err(|_| Error::ParseError)?;
+
+            manufacturer = Some(man);
+            manufacturer_id = Some(man_id);
+        }
+
+        // Port path, as device numbers change whenever a device is reconnected.
+        // Devices that were disconnected since `lsusb` ran are skipped.
+
+        let Some(dir) = sysfs::usb_device_dir(bus, device) else {
+            continue;
+        };
+
+        let Some(path) = sysfs::device_path(&dir) else {
+            continue;
+        };
+
+        // Serial
+
+        let serial = sysfs::read_attr(&dir, "serial");
+
+        // udev properties
+
+        let udev = read_udev_data(&dir).unwrap_or_default();
+
+        devices.push(DeviceInfo {
+            path,
+            class: class.to_owned(),
+            vendor: vendor.to_owned(),
+            product: product.to_owned(),
+            manufacturer: manufacturer.map(|s| s.to_owned()),
+            class_id: Some(class_id),
+            vendor_id,
+            product_id,
+            manufacturer_id,
+            serial,
+            numa_node: None,
+            local_cpus: None,
+            resources: Vec::new(),
+            compatible: Vec::new(),
+            acpi_hid: None,
+            udev_properties: udev.properties,
+            udev_tags: udev.tags,
+        });
+    }
+
+    Ok(devices)
+}
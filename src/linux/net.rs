@@ -0,0 +1,185 @@
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::{
+    mem,
+    os::fd::{
+        AsRawFd,
+        FromRawFd,
+        OwnedFd,
+    },
+    ptr,
+};
+
+use super::{
+    fixture,
+    sysfs,
+    DeviceLookup,
+};
+use crate::{
+    error::Error,
+    net::{
+        NetworkAdapter,
+        OperState,
+    },
+};
+
+const SYSFS_NET: &str = "/sys/class/net";
+
+// `addr_assign_type` values, see `NET_ADDR_*` in `netdevice.h`
+const NET_ADDR_PERM: &str = "0";
+
+/// `ETHTOOL_GPERMADDR`, see `ethtool.h`
+#[cfg(target_os = "linux")]
+const ETHTOOL_GPERMADDR: u32 = 0x20;
+
+/// `MAX_ADDR_LEN`, see `netdevice.h`
+#[cfg(target_os = "linux")]
+const MAX_ADDR_LEN: usize = 32;
+
+/// `struct ethtool_perm_addr`, with room for the longest address.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct EthtoolPermAddr {
+    cmd: u32,
+    size: u32,
+    data: [u8; MAX_ADDR_LEN],
+}
+
+/// Reads the permanent hardware address of an interface with the `ETHTOOL_GPERMADDR` ioctl,
+/// which does not require any privileges.
+#[cfg(target_os = "linux")]
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn read_permanent_address(interface: &str) -> Option<String> {
+    if interface.len() >= libc::IFNAMSIZ {
+        return None;
+    }
+
+    // SAFETY: a successfully opened descriptor is owned by nothing else
+    let socket = unsafe {
+        let fd = libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0);
+
+        (fd >= 0).then(|| OwnedFd::from_raw_fd(fd))
+    }?;
+
+    let mut request = EthtoolPermAddr {
+        cmd: ETHTOOL_GPERMADDR,
+        size: MAX_ADDR_LEN as u32,
+        data: [0; MAX_ADDR_LEN],
+    };
+
+    // SAFETY: `ifreq` is plain old data, and the ioctl writes at most `size` bytes of `data`
+    let result = unsafe {
+        let mut ifreq: libc::ifreq = mem::zeroed();
+
+        for (dst, src) in ifreq.ifr_name.iter_mut().zip(interface.bytes()) {
+            *dst = src as libc::c_char;
+        }
+
+        ifreq.ifr_ifru.ifru_data = ptr::addr_of_mut!(request).cast();
+
+        // The request is a `c_int` on musl and a `c_ulong` on glibc
+        let ethtool = libc::SIOCETHTOOL as libc::Ioctl;
+
+        libc::ioctl(socket.as_raw_fd(), ethtool, ptr::addr_of_mut!(ifreq))
+    };
+
+    if result < 0 {
+        return None;
+    }
+
+    let address = request.data.get(..request.size as usize)?;
+
+    // Interfaces without a permanent address report an empty or zero address
+    if address.iter().all(|b| *b == 0) {
+        return None;
+    }
+
+    let bytes: Vec<String> = address.iter().map(|b| format!("{b:02x}")).collect();
+
+    Some(bytes.join(":"))
+}
+
+/// Other platforms have no ethtool ioctl, so only sysfs is read.
+#[cfg(not(target_os = "linux"))]
+fn read_permanent_address(_interface: &str) -> Option<String> {
+    None
+}
+
+pub(crate) fn get_network_adapters() -> Result<Vec<NetworkAdapter>, Error> {
+    let entries = fs::read_dir(fixture::system_path(SYSFS_NET)).map_err(|_| Error::CommandError)?;
+
+    let mut lookup = DeviceLookup::default();
+    let mut adapters = Vec::new();
+
+    for entry in entries.flatten() {
+        let dir = entry.path();
+
+        // Virtual interfaces have no backing device
+        let Some(path) = sysfs::owning_device(&dir.join("device")) else {
+            continue;
+        };
+
+        let Some(device) = lookup.find(&path)? else {
+            continue;
+        };
+
+        let interface = entry.file_name().to_string_lossy().into_owned();
+        let mac_address = sysfs::read_attr(&dir, "address").filter(|a| !a.is_empty());
+
+        // Interfaces are not queried while replaying a fixture, which has no ioctls to answer
+        let permanent_mac_address = if fixture::is_replaying() {
+            None
+        } else {
+            read_permanent_address(&interface)
+        };
+
+        // Otherwise, the current address is the permanent one if it was never changed
+        let permanent_mac_address =
+            permanent_mac_address.or_else(|| match sysfs::read_attr(&dir, "addr_assign_type") {
+                Some(t) if t == NET_ADDR_PERM => mac_address.clone(),
+                _ => None,
+            });
+
+        let operstate = match sysfs::read_attr(&dir, "operstate").as_deref() {
+            Some("up") => OperState::Up,
+            Some("down") => OperState::Down,
+            Some("dormant") => OperState::Dormant,
+            Some("lowerlayerdown") => OperState::LowerLayerDown,
+            Some("notpresent") => OperState::NotPresent,
+            Some("testing") => OperState::Testing,
+            _ => OperState::Unknown,
+        };
+
+        // Reported as -1 when unknown
+        let speed = sysfs::read_attr(&dir, "speed").and_then(|s| s.parse::<u32>().ok());
+
+        adapters.push(NetworkAdapter {
+            device,
+            interface,
+            mac_address,
+            permanent_mac_address,
+            operstate,
+            speed,
+        });
+    }
+
+    adapters.sort();
+
+    Ok(adapters)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn struct_matches_kernel_layout() {
+        assert_eq!(mem::size_of::<EthtoolPermAddr>(), 8 + MAX_ADDR_LEN);
+    }
+
+    #[test]
+    fn unknown_interfaces_have_no_permanent_address() {
+        assert_eq!(read_permanent_address("devices-test0"), None);
+        assert_eq!(read_permanent_address("an-interface-name-too-long"), None);
+    }
+}
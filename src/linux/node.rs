@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use super::sysfs;
+use crate::{
+    error::Error,
+    node::DeviceNode,
+    path::DevicePath,
+};
+
+fn node_from_dir(dir: &Path) -> Option<DeviceNode> {
+    let uevent = sysfs::read_uevent(dir);
+    let subsystem = sysfs::subsystem(dir)?;
+
+    if let Some(interface) = uevent.get("INTERFACE") {
+        return Some(DeviceNode {
+            subsystem,
+            name: interface.clone(),
+            devnode: None,
+        });
+    }
+
+    let devname = uevent.get("DEVNAME")?;
+
+    Some(DeviceNode {
+        subsystem,
+        name: dir.file_name()?.to_string_lossy().into_owned(),
+        devnode: Some(format!("/dev/{devname}")),
+    })
+}
+
+pub(crate) fn get_nodes(path: &DevicePath) -> Result<Vec<DeviceNode>, Error> {
+    let dir = sysfs::device_dir(path).ok_or(Error::NotFound)?;
+
+    let mut nodes = Vec::new();
+
+    nodes.extend(node_from_dir(&dir));
+
+    sysfs::walk_children(&dir, &mut |child| {
+        nodes.extend(node_from_dir(child));
+    });
+
+    nodes.sort();
+
+    Ok(nodes)
+}
@@ -0,0 +1,138 @@
+use super::sysfs;
+use crate::{
+    error::Error,
+    path::DevicePath,
+    power::{
+        DevicePowerState,
+        PowerControl,
+        PowerState,
+        RuntimeStatus,
+    },
+};
+
+fn parse_device_state(s: &str) -> Option<DevicePowerState> {
+    match s {
+        "D0" => Some(DevicePowerState::D0),
+        "D1" => Some(DevicePowerState::D1),
+        "D2" => Some(DevicePowerState::D2),
+        "D3hot" => Some(DevicePowerState::D3Hot),
+        "D3cold" => Some(DevicePowerState::D3Cold),
+        _ => None,
+    }
+}
+
+fn parse_control(s: &str) -> Option<PowerControl> {
+    match s {
+        "auto" => Some(PowerControl::Auto),
+        "on" => Some(PowerControl::On),
+        _ => None,
+    }
+}
+
+fn parse_runtime_status(s: &str) -> Option<RuntimeStatus> {
+    match s {
+        "active" => Some(RuntimeStatus::Active),
+        "suspended" => Some(RuntimeStatus::Suspended),
+        "suspending" => Some(RuntimeStatus::Suspending),
+        "resuming" => Some(RuntimeStatus::Resuming),
+        "error" => Some(RuntimeStatus::Error),
+        "unsupported" => Some(RuntimeStatus::Unsupported),
+        _ => None,
+    }
+}
+
+/// Parse the `wakeup` attribute, which is empty for devices that cannot wake the system.
+fn parse_wakeup(s: &str) -> Option<bool> {
+    match s {
+        "enabled" => Some(true),
+        "disabled" => Some(false),
+        _ => None,
+    }
+}
+
+pub(crate) fn get_power_state(path: &DevicePath) -> Result<PowerState, Error> {
+    let dir = sysfs::device_dir(path).ok_or(Error::NotFound)?;
+    let power = dir.join("power");
+
+    Ok(PowerState {
+        device_state: sysfs::read_attr(&dir, "power_state").and_then(|s| parse_device_state(&s)),
+        control: sysfs::read_attr(&power, "control").and_then(|c| parse_control(&c)),
+        runtime_status: sysfs::read_attr(&power, "runtime_status")
+            .and_then(|s| parse_runtime_status(&s)),
+        autosuspend_delay_ms: sysfs::read_attr(&power, "autosuspend_delay_ms")
+            .and_then(|d| d.parse::<i32>().ok()),
+        wakeup: sysfs::read_attr(&power, "wakeup").and_then(|w| parse_wakeup(&w)),
+    })
+}
+
+pub(crate) fn set_power_control(path: &DevicePath, control: PowerControl) -> Result<(), Error> {
+    let dir = sysfs::device_dir(path).ok_or(Error::NotFound)?;
+
+    let value = match control {
+        PowerControl::Auto => "auto",
+        PowerControl::On => "on",
+    };
+
+    sysfs::write_attr(&dir.join("power"), "control", value)
+}
+
+pub(crate) fn set_autosuspend_delay(path: &DevicePath, delay_ms: i32) -> Result<(), Error> {
+    let dir = sysfs::device_dir(path).ok_or(Error::NotFound)?;
+
+    sysfs::write_attr(&dir.join("power"), "autosuspend_delay_ms", &delay_ms.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_device_states() {
+        let cases = [
+            ("D0", Some(DevicePowerState::D0)),
+            ("D1", Some(DevicePowerState::D1)),
+            ("D2", Some(DevicePowerState::D2)),
+            ("D3hot", Some(DevicePowerState::D3Hot)),
+            ("D3cold", Some(DevicePowerState::D3Cold)),
+            ("unknown", None),
+            ("error", None),
+        ];
+
+        for (s, state) in cases {
+            assert_eq!(parse_device_state(s), state, "{s}");
+        }
+    }
+
+    #[test]
+    fn parses_control() {
+        assert_eq!(parse_control("auto"), Some(PowerControl::Auto));
+        assert_eq!(parse_control("on"), Some(PowerControl::On));
+        assert_eq!(parse_control("off"), None);
+        assert_eq!(parse_control(""), None);
+    }
+
+    #[test]
+    fn parses_runtime_status() {
+        let cases = [
+            ("active", Some(RuntimeStatus::Active)),
+            ("suspended", Some(RuntimeStatus::Suspended)),
+            ("suspending", Some(RuntimeStatus::Suspending)),
+            ("resuming", Some(RuntimeStatus::Resuming)),
+            ("error", Some(RuntimeStatus::Error)),
+            ("unsupported", Some(RuntimeStatus::Unsupported)),
+            ("Active", None),
+            ("", None),
+        ];
+
+        for (s, status) in cases {
+            assert_eq!(parse_runtime_status(s), status, "{s}");
+        }
+    }
+
+    #[test]
+    fn parses_wakeup() {
+        assert_eq!(parse_wakeup("enabled"), Some(true));
+        assert_eq!(parse_wakeup("disabled"), Some(false));
+        assert_eq!(parse_wakeup(""), None);
+    }
+}
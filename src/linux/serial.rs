@@ -0,0 +1,90 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use super::{
+    fixture,
+    sysfs,
+    DeviceLookup,
+};
+use crate::{
+    error::Error,
+    path::DevicePath,
+    serial::SerialPort,
+};
+
+const SYSFS_TTY: &str = "/sys/class/tty";
+
+// `PORT_UNKNOWN` in `serial_core.h`, used for unpopulated legacy UARTs
+const PORT_UNKNOWN: &str = "0";
+
+fn driver(dir: &Path) -> Option<String> {
+    let link = fs::read_link(dir.join("driver")).ok()?;
+
+    Some(link.file_name()?.to_string_lossy().into_owned())
+}
+
+pub(crate) fn get_serial_ports() -> Result<Vec<SerialPort>, Error> {
+    let entries = fs::read_dir(fixture::system_path(SYSFS_TTY)).map_err(|_| Error::CommandError)?;
+
+    let mut lookup = DeviceLookup::default();
+    let mut ports = Vec::new();
+
+    for entry in entries.flatten() {
+        let dir = entry.path();
+
+        // Virtual terminals and pseudo terminals have no parent device
+        let Ok(parent) = fs::canonicalize(dir.join("device")) else {
+            continue;
+        };
+
+        if sysfs::read_attr(&dir, "type").as_deref() == Some(PORT_UNKNOWN) {
+            continue;
+        }
+
+        // Skip the port devices the serial core inserts between the UART and the tty
+        let parent = parent
+            .ancestors()
+            .find(|d| sysfs::subsystem(d).as_deref() != Some("serial-base"))
+            .unwrap_or(&parent);
+
+        let path = sysfs::owning_device(parent);
+
+        let device = match &path {
+            Some(path) => lookup.find(path)?,
+            None => None,
+        };
+
+        let mut interface = None;
+        let mut serial_number = None;
+
+        if let Some(DevicePath::USB { .. }) = path {
+            interface = parent
+                .ancestors()
+                .find_map(|d| sysfs::read_attr(d, "bInterfaceNumber"))
+                .and_then(|n| u8::from_str_radix(&n, 16).ok());
+
+            serial_number = parent
+                .ancestors()
+                .find(|d| sysfs::is_device(d))
+                .and_then(|d| sysfs::read_attr(d, "serial"));
+        }
+
+        ports.push(SerialPort {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            device,
+            interface,
+            serial_number,
+            parent: parent
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned()),
+            parent_subsystem: sysfs::subsystem(parent),
+            driver: driver(parent),
+        });
+    }
+
+    ports.sort();
+
+    Ok(ports)
+}
@@ -0,0 +1,385 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use super::{
+    fixture,
+    sysfs,
+    DeviceLookup,
+};
+use crate::{
+    error::Error,
+    sound::{
+        HdaCodec,
+        PcmDevice,
+        SoundCard,
+    },
+};
+
+const PROC_ASOUND: &str = "/proc/asound";
+const PROC_ASOUND_CARDS: &str = "/proc/asound/cards";
+const PROC_ASOUND_PCM: &str = "/proc/asound/pcm";
+const SYSFS_SOUND: &str = "/sys/class/sound";
+const SYSFS_HDAUDIO_DEVICES: &str = "/sys/bus/hdaudio/devices";
+
+fn hex_attr(dir: &Path, name: &str) -> Option<u32> {
+    let value = sysfs::read_attr(dir, name)?;
+
+    u32::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+}
+
+/// Parse `/proc/asound/pcm`, where each line is `<card>-<device>: <id> : <name> : playback <n> : capture <n>`.
+fn parse_pcms(output: &str) -> Result<Vec<PcmDevice>, Error> {
+    let mut pcms = Vec::new();
+
+    for line in output.lines() {
+        let (address, rest) = line.split_once(": ").ok_or(Error::ParseError)?;
+        let (card, device) = address.split_once('-').ok_or(Error::ParseError)?;
+
+        let mut fields = rest.split(" : ");
+        let _id = fields.next();
+        let name = fields.next().unwrap_or_default().trim().to_owned();
+        let streams: Vec<_> = fields.collect();
+
+        pcms.push(PcmDevice {
+            card: card.parse().map_err(|_| Error::ParseError)?,
+            device: device.parse().map_err(|_| Error::ParseError)?,
+            name,
+            playback: streams.iter().any(|s| s.starts_with("playback")),
+            capture: streams.iter().any(|s| s.starts_with("capture")),
+        });
+    }
+
+    Ok(pcms)
+}
+
+/// Parse `/proc/asound/card<n>/codec#<address>`, which starts with unindented `<field>: <value>`
+/// lines such as `Codec: Realtek ALC892` and `Vendor Id: 0x10ec0892`.
+fn parse_codec(output: &str) -> Option<HdaCodec> {
+    let field = |name: &str| {
+        output
+            .lines()
+            .filter_map(|l| l.split_once(": "))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.trim())
+    };
+
+    let hex = |name: &str| u32::from_str_radix(field(name)?.trim_start_matches("0x"), 16).ok();
+
+    // The codec is named by its vendor followed by its chip, as in the HD Audio bus attributes
+    let (vendor_name, chip_name) = match field("Codec").map(|c| c.split_once(' ')) {
+        Some(Some((vendor, chip))) => (Some(vendor.to_owned()), Some(chip.to_owned())),
+        Some(None) => (None, field("Codec").map(ToOwned::to_owned)),
+        None => (None, None),
+    };
+
+    Some(HdaCodec {
+        address: field("Address")?.parse().ok()?,
+        vendor_id: hex("Vendor Id")?,
+        subsystem_id: hex("Subsystem Id").unwrap_or_default(),
+        revision_id: hex("Revision Id").unwrap_or_default(),
+        vendor_name,
+        chip_name,
+    })
+}
+
+/// Returns the codecs of a card listed in `/proc`, for kernels before 4.1 without the HD Audio bus.
+fn proc_codecs(card: u32) -> Vec<HdaCodec> {
+    let dir = fixture::system_path(PROC_ASOUND).join(format!("card{card}"));
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut codecs: Vec<_> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("codec#"))
+        .filter_map(|e| parse_codec(&fs::read_to_string(e.path()).ok()?))
+        .collect();
+
+    codecs.sort();
+    codecs
+}
+
+fn codecs(card: u32) -> Vec<HdaCodec> {
+    let prefix = format!("hdaudioC{card}D");
+
+    let Ok(entries) = fs::read_dir(fixture::system_path(SYSFS_HDAUDIO_DEVICES)) else {
+        return proc_codecs(card);
+    };
+
+    let mut codecs: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let address = name.strip_prefix(&prefix)?.parse().ok()?;
+            let dir = entry.path();
+
+            Some(HdaCodec {
+                address,
+                vendor_id: hex_attr(&dir, "vendor_id")?,
+                subsystem_id: hex_attr(&dir, "subsystem_id").unwrap_or_default(),
+                revision_id: hex_attr(&dir, "revision_id").unwrap_or_default(),
+                vendor_name: sysfs::read_attr(&dir, "vendor_name"),
+                chip_name: sysfs::read_attr(&dir, "chip_name"),
+            })
+        })
+        .collect();
+
+    codecs.sort();
+    codecs
+}
+
+/// Parse `/proc/asound/cards`, without the devices, PCM devices and codecs of the cards.
+fn parse_cards(output: &str) -> Result<Vec<SoundCard>, Error> {
+    let mut cards = Vec::new();
+
+    // Each card is described by two lines:
+    // ` 0 [PCH            ]: HDA-Intel - HDA Intel PCH`
+    // `                      HDA Intel PCH at 0xf7f10000 irq 32`
+    let mut lines = output.lines().filter(|l| !l.trim().is_empty());
+
+    while let Some(line) = lines.next() {
+        if line.trim() == "--- no soundcards ---" {
+            break;
+        }
+
+        let (index, rest) = line.trim_start().split_once(' ').ok_or(Error::ParseError)?;
+        let index = index.parse::<u32>().map_err(|_| Error::ParseError)?;
+
+        let (id, rest) = rest
+            .trim_start()
+            .strip_prefix('[')
+            .and_then(|r| r.split_once("]: "))
+            .ok_or(Error::ParseError)?;
+
+        let (driver, name) = rest.split_once(" - ").ok_or(Error::ParseError)?;
+        let long_name = lines.next().unwrap_or_default().trim().to_owned();
+
+        cards.push(SoundCard {
+            index,
+            device: None,
+            id: id.trim().to_owned(),
+            driver: driver.trim().to_owned(),
+            name: name.trim().to_owned(),
+            long_name,
+            pcms: Vec::new(),
+            codecs: Vec::new(),
+        });
+    }
+
+    Ok(cards)
+}
+
+pub(crate) fn get_sound_cards() -> Result<Vec<SoundCard>, Error> {
+    let Ok(output) = fs::read_to_string(fixture::system_path(PROC_ASOUND_CARDS)) else {
+        // ALSA is not loaded
+        return Ok(Vec::new());
+    };
+
+    // Cards without PCM devices leave the file empty or absent
+    let mut pcms = match fs::read_to_string(fixture::system_path(PROC_ASOUND_PCM)) {
+        Ok(output) => parse_pcms(&output)?,
+        Err(_) => Vec::new(),
+    };
+
+    let mut lookup = DeviceLookup::default();
+    let mut cards = parse_cards(&output)?;
+
+    for card in &mut cards {
+        let dir = fixture::system_path(SYSFS_SOUND).join(format!("card{}", card.index));
+
+        card.device = match sysfs::owning_device(&dir.join("device")) {
+            Some(path) => lookup.find(&path)?,
+            None => None,
+        };
+
+        let (card_pcms, rest) = pcms.into_iter().partition(|p| p.card == card.index);
+        pcms = rest;
+
+        card.pcms = card_pcms;
+        card.codecs = codecs(card.index);
+    }
+
+    Ok(cards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARDS: &str = "\
+ 0 [PCH            ]: HDA-Intel - HDA Intel PCH
+                      HDA Intel PCH at 0xf7f10000 irq 32
+ 1 [NVidia         ]: HDA-Intel - HDA NVidia
+                      HDA NVidia at 0xf7080000 irq 17
+ 2 [Audio          ]: USB-Audio - USB Audio
+                      Generic USB Audio at usb-0000:00:14.0-2, high speed
+";
+
+    const PCMS: &str = "\
+00-00: ALC892 Analog : ALC892 Analog : playback 1 : capture 1
+00-01: ALC892 Digital : ALC892 Digital : playback 1
+00-02: ALC892 Alt Analog : ALC892 Alt Analog : capture 1
+01-03: HDMI 0 : HDMI 0 : playback 1
+02-00: USB Audio : USB Audio : playback 1 : capture 1
+";
+
+    const ALC892: &str = "\
+Codec: Realtek ALC892
+Address: 0
+AFG Function Id: 0x1 (unsol 1)
+Vendor Id: 0x10ec0892
+Subsystem Id: 0x1458a102
+Revision Id: 0x100302
+No Modem Function Group found
+Default PCM:
+    rates [0x560]: 44100 48000 96000 192000
+    bits [0xe]: 16 20 24
+    formats [0x1]: PCM
+Default Amp-In caps: N/A
+Default Amp-Out caps: N/A
+State of AFG node 0x01:
+  Power states:  D0 D1 D2 D3 CLKSTOP EPSS
+  Power: setting=D0, actual=D0
+GPIO: io=2, o=0, i=0, unsolicited=1, wake=0
+Node 0x02 [Audio Output] wcaps 0x41d: Stereo Amp-Out
+  Control: name=\"Front Playback Volume\", index=0, device=0
+  Device: name=\"ALC892 Analog\", type=\"Audio\", device=0
+";
+
+    const KABYLAKE_HDMI: &str = "\
+Codec: Intel Kabylake HDMI
+Address: 2
+AFG Function Id: 0x1 (unsol 0)
+Vendor Id: 0x8086280b
+Subsystem Id: 0x80860101
+Revision Id: 0x100000
+No Modem Function Group found
+Default PCM:
+    rates [0x0]:
+    bits [0x0]:
+    formats [0x0]:
+";
+
+    fn card(index: u32, id: &str, driver: &str, name: &str, long_name: &str) -> SoundCard {
+        SoundCard {
+            index,
+            device: None,
+            id: id.to_owned(),
+            driver: driver.to_owned(),
+            name: name.to_owned(),
+            long_name: long_name.to_owned(),
+            pcms: Vec::new(),
+            codecs: Vec::new(),
+        }
+    }
+
+    fn pcm(card: u32, device: u32, name: &str, playback: bool, capture: bool) -> PcmDevice {
+        PcmDevice {
+            card,
+            device,
+            name: name.to_owned(),
+            playback,
+            capture,
+        }
+    }
+
+    #[test]
+    fn parses_cards() {
+        let cases = [
+            (
+                CARDS,
+                vec![
+                    card(
+                        0,
+                        "PCH",
+                        "HDA-Intel",
+                        "HDA Intel PCH",
+                        "HDA Intel PCH at 0xf7f10000 irq 32",
+                    ),
+                    card(1, "NVidia", "HDA-Intel", "HDA NVidia", "HDA NVidia at 0xf7080000 irq 17"),
+                    card(
+                        2,
+                        "Audio",
+                        "USB-Audio",
+                        "USB Audio",
+                        "Generic USB Audio at usb-0000:00:14.0-2, high speed",
+                    ),
+                ],
+            ),
+            ("--- no soundcards ---\n", Vec::new()),
+            ("", Vec::new()),
+        ];
+
+        for (output, cards) in cases {
+            assert_eq!(parse_cards(output).unwrap(), cards, "{output}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_cards() {
+        for output in [" x [PCH            ]: HDA-Intel - HDA Intel PCH\n", " 0 PCH: HDA-Intel\n"] {
+            assert!(matches!(parse_cards(output), Err(Error::ParseError)), "{output}");
+        }
+    }
+
+    #[test]
+    fn parses_pcms() {
+        let cases = [
+            (
+                PCMS,
+                vec![
+                    pcm(0, 0, "ALC892 Analog", true, true),
+                    pcm(0, 1, "ALC892 Digital", true, false),
+                    pcm(0, 2, "ALC892 Alt Analog", false, true),
+                    pcm(1, 3, "HDMI 0", true, false),
+                    pcm(2, 0, "USB Audio", true, true),
+                ],
+            ),
+            ("", Vec::new()),
+        ];
+
+        for (output, pcms) in cases {
+            assert_eq!(parse_pcms(output).unwrap(), pcms, "{output}");
+        }
+
+        assert_eq!(parse_pcms(PCMS).unwrap()[3].alsa_name(), "hw:1,3");
+        assert!(matches!(parse_pcms("00: HDMI 0 : HDMI 0\n"), Err(Error::ParseError)));
+    }
+
+    #[test]
+    fn parses_codecs() {
+        let cases = [
+            (
+                ALC892,
+                Some(HdaCodec {
+                    address: 0,
+                    vendor_id: 0x10ec_0892,
+                    subsystem_id: 0x1458_a102,
+                    revision_id: 0x0010_0302,
+                    vendor_name: Some("Realtek".to_owned()),
+                    chip_name: Some("ALC892".to_owned()),
+                }),
+            ),
+            (
+                KABYLAKE_HDMI,
+                Some(HdaCodec {
+                    address: 2,
+                    vendor_id: 0x8086_280b,
+                    subsystem_id: 0x8086_0101,
+                    revision_id: 0x0010_0000,
+                    vendor_name: Some("Intel".to_owned()),
+                    chip_name: Some("Kabylake HDMI".to_owned()),
+                }),
+            ),
+            ("Codec: Realtek ALC892\nAddress: 0\n", None),
+            ("", None),
+        ];
+
+        for (output, codec) in cases {
+            assert_eq!(parse_codec(output), codec, "{output}");
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use super::fixture;
+use crate::{
+    error::Error,
+    path::DevicePath,
+    resource::PciResource,
+};
+
+pub(crate) const SYSFS_BUS: &str = "/sys/bus";
+pub(crate) const SYSFS_PCI_DEVICES: &str = "/sys/bus/pci/devices";
+pub(crate) const SYSFS_USB_DEVICES: &str = "/sys/bus/usb/devices";
+
+/// Read a sysfs attribute, returning its trimmed contents.
+pub(crate) fn read_attr(dir: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|s| s.trim().to_owned())
+}
+
+/// Read the `uevent` attribute of a device as key-value pairs.
+pub(crate) fn read_uevent(dir: &Path) -> BTreeMap<String, String> {
+    read_attr(dir, "uevent")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .collect()
+}
+
+/// Returns the name of the subsystem a sysfs device belongs to.
+pub(crate) fn subsystem(dir: &Path) -> Option<String> {
+    let link = fs::read_link(dir.join("subsystem")).ok()?;
+
+    Some(link.file_name()?.to_string_lossy().into_owned())
+}
+
+/// Returns the part of a sysfs path starting at `/devices`, which udev calls the `DEVPATH`.
+///
+/// Paths in other copies of sysfs start at the nearest `devices` directory above them.
+pub(crate) fn devpath(dir: &Path) -> Option<String> {
+    let root = fixture::system_path("/sys");
+
+    // Paths are canonical, so the root of a fixture being replayed must be too
+    let root = fs::canonicalize(&root).unwrap_or(root);
+
+    let top = if dir.starts_with(&root) {
+        root.join("devices")
+    } else {
+        dir.ancestors().find(|d| d.file_name() == Some("devices".as_ref()))?.to_owned()
+    };
+
+    let rest = dir.strip_prefix(top).ok()?;
+
+    Some(format!("/devices/{}", rest.display()))
+}
+
+/// Returns `true` if the sysfs directory is a device on a supported bus, as opposed to an interface or class device.
+pub(crate) fn is_device(dir: &Path) -> bool {
+    device_path(dir).is_some()
+}
+
+/// Returns the path of the device that a sysfs directory belongs to.
+///
+/// Symbolic links, such as `/sys/class/net/eth0/device`, are resolved first.
+pub(crate) fn owning_device(dir: &Path) -> Option<DevicePath> {
+    let dir = fs::canonicalize(dir).ok()?;
+
+    dir.ancestors().find_map(device_path)
+}
+
+/// Returns the path of a sysfs device directory on a supported bus.
+pub(crate) fn device_path(dir: &Path) -> Option<DevicePath> {
+    let name = dir.file_name()?.to_str()?.to_owned();
+
+    match subsystem(dir)?.as_str() {
+        // Devices are named by their port path, such as `1-2.3`, and interfaces
+        // by their port path, configuration and interface, such as `1-2.3:1.0`
+        "usb" if !name.contains(':') => DevicePath::from_usb_port_path(&name).ok(),
+        // PCI devices are named `<domain>:<bus>:<slot>.<function>`
+        "pci" => DevicePath::from_lspci(&name).ok(),
+        "platform" => Some(DevicePath::Platform { name }),
+        // Adapters are also on the I2C bus, and are named `i2c-<n>`
+        "i2c" if !is_i2c_adapter(&name) => Some(DevicePath::I2C { name }),
+        "spi" => Some(DevicePath::SPI { name }),
+        "sdio" => Some(DevicePath::SDIO { name }),
+        // Domains, retimers and XDomain connections are also on the Thunderbolt bus
+        "thunderbolt" if is_thunderbolt_router(&name) => Some(DevicePath::Thunderbolt { name }),
+        _ => None,
+    }
+}
+
+fn is_i2c_adapter(name: &str) -> bool {
+    name.strip_prefix("i2c-")
+        .is_some_and(|n| n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_thunderbolt_router(name: &str) -> bool {
+    name.split_once('-').is_some_and(|(domain, route)| {
+        domain.bytes().all(|b| b.is_ascii_digit()) && route.bytes().all(|b| b.is_ascii_hexdigit())
+    })
+}
+
+/// Visit every descendant of a device, without descending into other devices.
+pub(crate) fn walk_children(dir: &Path, visit: &mut impl FnMut(&Path)) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        // Skip links such as `subsystem`, `driver` and `port`
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+
+        let child = entry.path();
+
+        if is_device(&child) {
+            continue;
+        }
+
+        visit(&child);
+        walk_children(&child, visit);
+    }
+}
+
+/// Write a sysfs attribute.
+pub(crate) fn write_attr(dir: &Path, name: &str, value: &str) -> Result<(), Error> {
+    fs::write(dir.join(name), value).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => Error::NotFound,
+        _ => Error::CommandError,
+    })
+}
+
+/// Returns the sysfs directory of a device, if it exists.
+pub(crate) fn device_dir(path: &DevicePath) -> Option<PathBuf> {
+    let dir = match path {
+        DevicePath::PCI {
+            domain,
+            bus,
+            slot,
+            function,
+        } => fixture::system_path(SYSFS_PCI_DEVICES)
+            .join(format!("{domain:04x}:{bus:02x}:{slot:02x}.{function:x}")),
+        DevicePath::USB { .. } => {
+            fixture::system_path(SYSFS_USB_DEVICES).join(path.to_usb_port_path()?)
+        }
+        DevicePath::Platform { name }
+        | DevicePath::I2C { name }
+        | DevicePath::SPI { name }
+        | DevicePath::SDIO { name }
+        | DevicePath::Thunderbolt { name } => fixture::system_path(SYSFS_BUS)
+            .join(path.bus())
+            .join("devices")
+            .join(name),
+    };
+
+    dir.exists().then_some(dir)
+}
+
+/// Returns the sysfs directory of the USB device with the given bus and device number.
+pub(crate) fn usb_device_dir(bus: u8, device: u8) -> Option<PathBuf> {
+    let entries = fs::read_dir(fixture::system_path(SYSFS_USB_DEVICES)).ok()?;
+
+    for entry in entries.flatten() {
+        // Interfaces are named `<port>:<config>.<interface>`
+        if entry.file_name().to_string_lossy().contains(':') {
+            continue;
+        }
+
+        let dir = entry.path();
+
+        let busnum = read_attr(&dir, "busnum").and_then(|b| b.parse::<u8>().ok());
+        let devnum = read_attr(&dir, "devnum").and_then(|d| d.parse::<u8>().ok());
+
+        if busnum == Some(bus) && devnum == Some(device) {
+            return Some(dir);
+        }
+    }
+
+    None
+}
+
+/// Parse a kernel cpulist such as `0-3,8,10-11`.
+pub(crate) fn parse_cpulist(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+
+    for range in list.trim().split(',').filter(|r| !r.is_empty()) {
+        if let Some((start, end)) = range.split_once('-') {
+            let start = start.parse::<usize>().ok()?;
+            let end = end.parse::<usize>().ok()?;
+
+            cpus.extend(start..=end);
+        } else {
+            cpus.push(range.parse::<usize>().ok()?);
+        }
+    }
+
+    Some(cpus)
+}
+
+/// Parse a line of the `resource` attribute of a PCI device, formatted as `<start> <end> <flags>`.
+///
+/// Unused entries, which are all zeros, and entries that do not describe a valid range are skipped.
+fn parse_resource(index: usize, line: &str) -> Option<PciResource> {
+    let mut fields = line
+        .split_whitespace()
+        .map(|f| u64::from_str_radix(f.trim_start_matches("0x"), 16));
+
+    let start = fields.next()?.ok()?;
+    let end = fields.next()?.ok()?;
+    let flags = fields.next()?.ok()?;
+
+    if flags == 0 && end <= start {
+        return None;
+    }
+
+    Some(PciResource {
+        index: u8::try_from(index).ok()?,
+        start,
+        size: end.checked_sub(start)?.checked_add(1)?,
+        flags,
+    })
+}
+
+/// Parse the `resource` attribute of a PCI device, skipping unused and invalid entries.
+pub(crate) fn parse_resources(contents: &str) -> Vec<PciResource> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| parse_resource(index, line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn devpaths_start_below_the_replayed_root() {
+        // The fixture itself is below a `devices` directory
+        let root = std::env::temp_dir()
+            .join(format!("devices-sysfs-{}", std::process::id()))
+            .join("devices");
+        let dir = root.join("sys/devices/pci0000:00/0000:00:1f.3");
+
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&dir).unwrap();
+
+        let replayed = fixture::replay_fixture(&root, || devpath(&fs::canonicalize(&dir).unwrap()));
+
+        assert_eq!(replayed.as_deref(), Some("/devices/pci0000:00/0000:00:1f.3"));
+
+        // The same tree read as a copy of sysfs
+        let copied = devpath(&fs::canonicalize(&dir).unwrap());
+
+        assert_eq!(copied.as_deref(), Some("/devices/pci0000:00/0000:00:1f.3"));
+
+        let dir = Path::new("/sys/devices/platform/serial8250");
+
+        assert_eq!(devpath(dir).as_deref(), Some("/devices/platform/serial8250"));
+        assert_eq!(devpath(Path::new("/sys/class/net")), None);
+
+        fs::remove_dir_all(root.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn parses_cpulists() {
+        assert_eq!(parse_cpulist("0-3,8,10-11\n"), Some(vec![0, 1, 2, 3, 8, 10, 11]));
+        assert_eq!(parse_cpulist(""), Some(Vec::new()));
+        assert_eq!(parse_cpulist("0-x"), None);
+    }
+
+    #[test]
+    fn parses_resources() {
+        let contents = "\
+0x00000000f7000000 0x00000000f701ffff 0x0000000000040200
+0x0000000000000000 0x0000000000000000 0x0000000000000000
+0x000000000000f000 0x000000000000f01f 0x0000000000040101
+";
+
+        let resources = parse_resources(contents);
+
+        assert_eq!(
+            resources,
+            [
+                PciResource {
+                    index: 0,
+                    start: 0xf700_0000,
+                    size: 0x2_0000,
+                    flags: 0x0004_0200,
+                },
+                PciResource {
+                    index: 2,
+                    start: 0xf000,
+                    size: 0x20,
+                    flags: 0x0004_0101,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_invalid_resources() {
+        let contents = "\
+0x0000000000000000 0xffffffffffffffff 0x0000000000000200
+0x00000000f7000000 0x00000000e7000000 0x0000000000040200
+garbage
+0x00000000f7000000
+0x00000000f7000000 0x00000000f7000fff 0x0000000000040200
+";
+
+        let resources = parse_resources(contents);
+
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].index, 4);
+        assert_eq!(resources[0].size, 0x1000);
+    }
+}
@@ -0,0 +1,426 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use super::{
+    bus,
+    fixture,
+    sysfs,
+    DeviceLookup,
+};
+use crate::{
+    error::Error,
+    path::DevicePath,
+    thunderbolt::{
+        ThunderboltAuthorization,
+        ThunderboltDevice,
+        ThunderboltDomain,
+        ThunderboltSecurity,
+    },
+};
+
+const SYSFS_THUNDERBOLT_DEVICES: &str = "/sys/bus/thunderbolt/devices";
+
+/// Returns the PCI Express downstream ports a host controller tunnels PCI devices through.
+///
+/// The driver links these ports to the native host interface (NHI) of the controller as consumers.
+/// Discrete controllers without such links expose them as the other downstream ports of the
+/// PCI Express switch the NHI sits behind.
+fn downstream_ports(nhi: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(nhi) else {
+        return Vec::new();
+    };
+
+    let linked: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("consumer:pci:"))
+        .filter_map(|e| fs::canonicalize(e.path().join("consumer")).ok())
+        .collect();
+
+    if !linked.is_empty() {
+        return linked;
+    }
+
+    let is_pci = |dir: &Path| {
+        dir.file_name()
+            .is_some_and(|n| DevicePath::from_lspci(&n.to_string_lossy()).is_ok())
+    };
+
+    let Some(port) = nhi.parent().filter(|p| is_pci(p)) else {
+        return Vec::new();
+    };
+
+    let Some(Ok(siblings)) = port.parent().filter(|p| is_pci(p)).map(fs::read_dir) else {
+        return Vec::new();
+    };
+
+    siblings
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p != port && is_pci(p))
+        .collect()
+}
+
+/// Returns the sysfs directories of PCI devices behind external-facing ports.
+fn removable_pci_dirs() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(fixture::system_path(sysfs::SYSFS_PCI_DEVICES)) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|e| sysfs::read_attr(&e.path(), "removable").as_deref() == Some("removable"))
+        .filter_map(|e| fs::canonicalize(e.path()).ok())
+        .collect()
+}
+
+/// Returns the route of a router from its name, such as `0x301` for `0-301`.
+fn route(name: &str) -> Option<u64> {
+    let (_, route) = name.split_once('-')?;
+
+    u64::from_str_radix(route, 16).ok()
+}
+
+/// Returns the route of the router a router is connected to.
+///
+/// Each byte of a route, from the lowest, is the port taken at the next hop from the host router.
+fn parent_route(route: u64) -> u64 {
+    let hops = (u64::BITS - route.leading_zeros()).div_ceil(8);
+
+    route & !(0xff << (8 * hops.saturating_sub(1)))
+}
+
+/// Returns the upstream ports of the PCI Express switches of docks, given the removable PCI
+/// devices behind the downstream ports of a host controller.
+///
+/// The first device behind a port is the upstream port of a dock, and its children are the
+/// downstream ports of the dock. A device on a downstream port with devices below it is the
+/// upstream port of the next dock in the chain.
+fn dock_switches(ports: &[PathBuf], dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut switches = BTreeSet::new();
+
+    for dir in dirs {
+        let Some(port) = ports.iter().find(|p| dir != *p && dir.starts_with(p)) else {
+            continue;
+        };
+
+        let Ok(hops) = dir.strip_prefix(port) else {
+            continue;
+        };
+
+        let count = hops.components().count();
+        let mut path = port.clone();
+
+        for (i, hop) in hops.components().enumerate() {
+            path.push(hop);
+
+            if i % 2 == 0 && (i == 0 || i + 1 < count) {
+                switches.insert(path.clone());
+            }
+        }
+    }
+
+    switches.into_iter().collect()
+}
+
+/// Returns the index of the dock switch a device sits behind, or is the upstream port of.
+fn nearest_switch(switches: &[PathBuf], dir: &Path) -> Option<usize> {
+    switches
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| dir.starts_with(s))
+        .max_by_key(|(_, s)| s.components().count())
+        .map(|(i, _)| i)
+}
+
+/// Pairs dock switches with the routes of the routers of a domain, returning their indices.
+///
+/// Switches and routers are connected in the same tree. The routers connected to a router are
+/// paired in the order of their ports with the switches connected to its switch, in the order of
+/// their PCI addresses, and are left unpaired if their numbers differ.
+fn pair_switches(switches: &[PathBuf], routes: &[u64]) -> Vec<(usize, usize)> {
+    let parent_switch = |i: usize| nearest_switch(switches, switches[i].parent()?);
+
+    let mut pairs = Vec::new();
+    let mut pending = vec![(None, 0)];
+
+    while let Some((switch, route)) = pending.pop() {
+        let child_switches: Vec<usize> =
+            (0..switches.len()).filter(|&i| parent_switch(i) == switch).collect();
+
+        let mut child_routes: Vec<usize> = (0..routes.len())
+            .filter(|&i| routes[i] != 0 && parent_route(routes[i]) == route)
+            .collect();
+
+        child_routes.sort_by_key(|&i| routes[i]);
+
+        if child_switches.len() != child_routes.len() {
+            continue;
+        }
+
+        for (s, r) in child_switches.into_iter().zip(child_routes) {
+            pairs.push((s, r));
+            pending.push((Some(s), routes[r]));
+        }
+    }
+
+    pairs
+}
+
+pub(crate) fn get_thunderbolt_domains() -> Result<Vec<ThunderboltDomain>, Error> {
+    let Ok(entries) = fs::read_dir(fixture::system_path(SYSFS_THUNDERBOLT_DEVICES)) else {
+        // The Thunderbolt bus only exists once the driver is loaded
+        return Ok(Vec::new());
+    };
+
+    let mut lookup = DeviceLookup::default();
+    let mut domains = Vec::new();
+    let mut domain_ports = Vec::new();
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        if !name.starts_with("domain") {
+            continue;
+        }
+
+        let dir = entry.path();
+
+        let security = match sysfs::read_attr(&dir, "security").as_deref() {
+            Some("none") => ThunderboltSecurity::None,
+            Some("user") => ThunderboltSecurity::User,
+            Some("secure") => ThunderboltSecurity::Secure,
+            Some("dponly") => ThunderboltSecurity::DpOnly,
+            Some("usbonly") => ThunderboltSecurity::UsbOnly,
+            Some("nopcie") => ThunderboltSecurity::NoPcie,
+            _ => ThunderboltSecurity::Unknown,
+        };
+
+        let ports = fs::canonicalize(&dir)
+            .ok()
+            .and_then(|d| d.parent().map(downstream_ports))
+            .unwrap_or_default();
+
+        let controller = match sysfs::owning_device(&dir) {
+            Some(path) => lookup.find(&path)?,
+            None => None,
+        };
+
+        domain_ports.push(ports);
+
+        let iommu_dma_protection =
+            sysfs::read_attr(&dir, "iommu_dma_protection").as_deref() == Some("1");
+
+        domains.push(ThunderboltDomain {
+            name,
+            controller,
+            security,
+            iommu_dma_protection,
+            devices: Vec::new(),
+        });
+    }
+
+    // Routers are named `<domain>-<route>`, where the host router has route `0`
+    for device in bus::get_bus("thunderbolt")? {
+        let DevicePath::Thunderbolt { name } = &device.path else {
+            continue;
+        };
+
+        let Some((domain, route)) = name.split_once('-') else {
+            continue;
+        };
+
+        let Some(domain) = domains.iter_mut().find(|d| d.name == format!("domain{domain}")) else {
+            continue;
+        };
+
+        let dir = fixture::system_path(SYSFS_THUNDERBOLT_DEVICES).join(name);
+
+        let authorization = match sysfs::read_attr(&dir, "authorized").as_deref() {
+            Some("1") => ThunderboltAuthorization::Authorized,
+            Some("2") => ThunderboltAuthorization::AuthorizedWithKey,
+            _ => ThunderboltAuthorization::Unauthorized,
+        };
+
+        domain.devices.push(ThunderboltDevice {
+            unique_id: sysfs::read_attr(&dir, "unique_id"),
+            authorization,
+            generation: sysfs::read_attr(&dir, "generation").and_then(|g| g.parse().ok()),
+            host: route == "0",
+            tunneled: Vec::new(),
+            device,
+        });
+    }
+
+    let removable = removable_pci_dirs();
+
+    // Tunneled PCI devices belong to the router whose dock switch they sit behind
+    for (domain, ports) in domains.iter_mut().zip(&domain_ports) {
+        let switches = dock_switches(ports, &removable);
+
+        let routes: Vec<u64> = domain
+            .devices
+            .iter()
+            .map(|d| match &d.device.path {
+                DevicePath::Thunderbolt { name } => route(name).unwrap_or(0),
+                _ => 0,
+            })
+            .collect();
+
+        for (switch, router) in pair_switches(&switches, &routes) {
+            for dir in &removable {
+                if nearest_switch(&switches, dir) != Some(switch) {
+                    continue;
+                }
+
+                let Some(path) = sysfs::device_path(dir) else {
+                    continue;
+                };
+
+                if let Some(device) = lookup.find(&path)? {
+                    domain.devices[router].tunneled.push(device);
+                }
+            }
+        }
+    }
+
+    for domain in &mut domains {
+        for device in &mut domain.devices {
+            device.tunneled.sort();
+        }
+
+        domain.devices.sort();
+    }
+
+    domains.sort();
+
+    Ok(domains)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::symlink;
+
+    use super::*;
+
+    fn tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("devices-tb-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    #[test]
+    fn linked_downstream_ports() {
+        let root = tree("linked");
+        let bus = root.join("devices/pci0000:00");
+        let nhi = bus.join("0000:00:0d.2");
+        let link = root.join("devices/virtual/devlink/pci:0000:00:0d.2--pci:0000:00:07.0");
+
+        fs::create_dir_all(&nhi).unwrap();
+        fs::create_dir_all(bus.join("0000:00:07.0")).unwrap();
+        fs::create_dir_all(bus.join("0000:00:14.0")).unwrap();
+        fs::create_dir_all(&link).unwrap();
+        symlink(bus.join("0000:00:07.0"), link.join("consumer")).unwrap();
+        symlink(&link, nhi.join("consumer:pci:0000:00:07.0")).unwrap();
+
+        let ports = downstream_ports(&fs::canonicalize(&nhi).unwrap());
+
+        assert_eq!(ports, [fs::canonicalize(bus.join("0000:00:07.0")).unwrap()]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn sibling_downstream_ports() {
+        let root = tree("siblings");
+        let upstream = root.join("devices/pci0000:00/0000:00:1c.0/0000:03:00.0");
+
+        fs::create_dir_all(upstream.join("0000:04:00.0/0000:05:00.0")).unwrap();
+        fs::create_dir_all(upstream.join("0000:04:01.0")).unwrap();
+        fs::create_dir_all(upstream.join("0000:04:02.0")).unwrap();
+        fs::create_dir_all(upstream.join("power")).unwrap();
+
+        let mut ports = downstream_ports(&upstream.join("0000:04:00.0/0000:05:00.0"));
+        ports.sort();
+
+        assert_eq!(ports, [upstream.join("0000:04:01.0"), upstream.join("0000:04:02.0")]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn root_bus_without_links_has_no_ports() {
+        let root = tree("root");
+        let nhi = root.join("devices/pci0000:00/0000:00:0d.2");
+
+        fs::create_dir_all(&nhi).unwrap();
+        fs::create_dir_all(root.join("devices/pci0000:00/0000:00:07.0")).unwrap();
+
+        assert!(downstream_ports(&nhi).is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn routes() {
+        assert_eq!(route("0-0"), Some(0));
+        assert_eq!(route("0-301"), Some(0x301));
+        assert_eq!(route("domain0"), None);
+
+        assert_eq!(parent_route(0x1), 0);
+        assert_eq!(parent_route(0x301), 0x1);
+        assert_eq!(parent_route(0x10301), 0x301);
+    }
+
+    /// Two docks chained behind the first port of the host, and one behind the second.
+    #[test]
+    fn chained_docks() {
+        let root = PathBuf::from("/sys/devices/pci0000:00");
+        let ports = [root.join("0000:00:07.0"), root.join("0000:00:07.2")];
+
+        let first = ports[0].join("0000:05:00.0");
+        let second = first.join("0000:06:01.0/0000:08:00.0");
+        let third = ports[1].join("0000:40:00.0");
+
+        let dirs = [
+            first.clone(),
+            first.join("0000:06:00.0"),
+            first.join("0000:06:02.0/0000:07:00.0"),
+            first.join("0000:06:01.0"),
+            second.clone(),
+            second.join("0000:09:02.0/0000:0a:00.0"),
+            third.join("0000:41:02.0/0000:42:00.0"),
+        ];
+
+        let switches = dock_switches(&ports, &dirs);
+
+        assert_eq!(switches, [first, second, third]);
+
+        // The USB controller of the first dock, and the bridge the second dock is connected to
+        assert_eq!(nearest_switch(&switches, &dirs[2]), Some(0));
+        assert_eq!(nearest_switch(&switches, &dirs[3]), Some(0));
+        assert_eq!(nearest_switch(&switches, &dirs[5]), Some(1));
+
+        let mut pairs = pair_switches(&switches, &[0x0, 0x1, 0x301, 0x3]);
+        pairs.sort_unstable();
+
+        assert_eq!(pairs, [(0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn ambiguous_docks_are_unpaired() {
+        let root = PathBuf::from("/sys/devices/pci0000:00");
+        let ports = [root.join("0000:00:07.0")];
+        let dirs = [ports[0].join("0000:05:00.0/0000:06:00.0")];
+
+        let switches = dock_switches(&ports, &dirs);
+
+        assert_eq!(switches, [ports[0].join("0000:05:00.0")]);
+        assert!(pair_switches(&switches, &[0x0, 0x1, 0x3]).is_empty());
+    }
+}
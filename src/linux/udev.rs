@@ -0,0 +1,466 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use super::{
+    fixture,
+    sysfs,
+};
+use crate::{
+    error::Error,
+    glob::glob_match,
+    udev::{
+        AssignKey,
+        AssignOp,
+        MatchKey,
+        RuleAssignment,
+        RuleMatch,
+        UdevData,
+        UdevEvaluation,
+        UdevRule,
+        UdevRules,
+    },
+};
+
+pub(super) const UDEV_DATA_DIR: &str = "/run/udev/data";
+
+/// A sysfs device directory, with the values rules match on.
+struct RuleDevice {
+    dir: PathBuf,
+    kernel: String,
+    subsystem: Option<String>,
+    driver: Option<String>,
+}
+
+impl RuleDevice {
+    fn new(dir: &Path) -> Self {
+        let driver = fs::read_link(dir.join("driver"))
+            .ok()
+            .and_then(|l| Some(l.file_name()?.to_string_lossy().into_owned()));
+
+        Self {
+            dir: dir.to_owned(),
+            kernel: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            subsystem: sysfs::subsystem(dir),
+            driver,
+        }
+    }
+
+    fn attr(&self, name: &str) -> Option<String> {
+        sysfs::read_attr(&self.dir, name)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    evaluation: UdevEvaluation,
+    finals: BTreeSet<AssignKey>,
+}
+
+/// Matches a value against a pattern with `|` separated alternatives.
+fn pattern_matches(pattern: &str, value: &str) -> bool {
+    pattern.split('|').any(|p| glob_match(p, value))
+}
+
+fn match_value(rule_match: &RuleMatch, device: &RuleDevice, state: &State) -> Option<String> {
+    let properties = &state.evaluation.properties;
+
+    match &rule_match.key {
+        MatchKey::Action => Some("add".to_owned()),
+        MatchKey::DevPath => Some(properties.get("DEVPATH").cloned().unwrap_or_default()),
+        MatchKey::Kernel | MatchKey::Kernels => Some(device.kernel.clone()),
+        MatchKey::Subsystem | MatchKey::Subsystems => {
+            Some(device.subsystem.clone().unwrap_or_default())
+        }
+        MatchKey::Driver | MatchKey::Drivers => Some(device.driver.clone().unwrap_or_default()),
+        MatchKey::Attr(name) | MatchKey::Attrs(name) => device.attr(name),
+        MatchKey::Env(key) => Some(properties.get(key).cloned().unwrap_or_default()),
+        MatchKey::Tag => None,
+    }
+}
+
+fn single_match(rule_match: &RuleMatch, device: &RuleDevice, state: &State) -> bool {
+    if rule_match.key == MatchKey::Tag {
+        let tagged = state
+            .evaluation
+            .tags
+            .iter()
+            .any(|t| pattern_matches(&rule_match.value, t));
+
+        return tagged != rule_match.negated;
+    }
+
+    // Missing attributes never match, whatever the operator
+    match match_value(rule_match, device, state) {
+        Some(value) => pattern_matches(&rule_match.value, &value) != rule_match.negated,
+        None => false,
+    }
+}
+
+fn rule_matches(rule: &UdevRule, devices: &[RuleDevice], state: &State) -> bool {
+    let (parent_keys, own_keys): (Vec<_>, Vec<_>) =
+        rule.matches.iter().partition(|m| m.key.is_parent_key());
+
+    if !own_keys.iter().all(|m| single_match(m, &devices[0], state)) {
+        return false;
+    }
+
+    // Keys matching parents must all match the same device
+    parent_keys.is_empty()
+        || devices
+            .iter()
+            .any(|d| parent_keys.iter().all(|m| single_match(m, d, state)))
+}
+
+/// Expands substitutions such as `%k` and `$attr{file}` in an assigned value.
+fn substitute(value: &str, devices: &[RuleDevice], state: &State) -> String {
+    let device = &devices[0];
+    let properties = &state.evaluation.properties;
+
+    let mut out = String::new();
+    let mut rest = value;
+
+    while let Some(i) = rest.find(['%', '$']) {
+        out.push_str(&rest[..i]);
+
+        let marker = &rest[i..=i];
+        let after = &rest[i + 1..];
+
+        // Long forms such as `$kernel`, and short forms such as `%k`
+        let (name, after) = if marker == "$" {
+            let end = after
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(after.len());
+            after.split_at(end)
+        } else {
+            after.split_at(after.chars().next().map_or(0, char::len_utf8))
+        };
+
+        let (arg, after) = match after.strip_prefix('{').and_then(|a| a.split_once('}')) {
+            Some((arg, after)) => (Some(arg), after),
+            None => (None, after),
+        };
+
+        let expanded = match (name, arg) {
+            ("k" | "kernel", None) => Some(device.kernel.clone()),
+            ("n" | "number", None) => {
+                let number = device.kernel.trim_end_matches(|c: char| c.is_ascii_digit());
+                Some(device.kernel[number.len()..].to_owned())
+            }
+            ("p" | "devpath", None) => properties.get("DEVPATH").cloned(),
+            ("d" | "driver", None) => device.driver.clone(),
+            ("N" | "devnode", None) => properties.get("DEVNAME").cloned(),
+            ("s" | "attr", Some(attr)) => devices.iter().find_map(|d| d.attr(attr)),
+            ("E" | "env", Some(key)) => properties.get(key).cloned(),
+            ("%", None) if marker == "%" => Some("%".to_owned()),
+            ("", None) if marker == "$" && after.starts_with('$') => {
+                rest = &after[1..];
+                out.push('$');
+                continue;
+            }
+            _ => None,
+        };
+
+        if let Some(expanded) = expanded {
+            out.push_str(&expanded);
+            rest = after;
+        } else {
+            out.push_str(marker);
+            rest = &rest[i + 1..];
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn update_list(list: &mut Vec<String>, op: AssignOp, value: &str) {
+    let values = value.split_whitespace().map(str::to_owned);
+
+    match op {
+        AssignOp::Set | AssignOp::Final => *list = values.collect(),
+        AssignOp::Add => {
+            for value in values {
+                if !list.contains(&value) {
+                    list.push(value);
+                }
+            }
+        }
+        AssignOp::Remove => {
+            let removed: Vec<String> = values.collect();
+            list.retain(|v| !removed.contains(v));
+        }
+    }
+}
+
+fn apply(assignment: &RuleAssignment, devices: &[RuleDevice], state: &mut State) {
+    if state.finals.contains(&assignment.key) {
+        return;
+    }
+
+    if assignment.op == AssignOp::Final {
+        state.finals.insert(assignment.key.clone());
+    }
+
+    let value = substitute(&assignment.value, devices, state);
+    let evaluation = &mut state.evaluation;
+
+    match &assignment.key {
+        AssignKey::Env(key) => {
+            if assignment.op == AssignOp::Remove || value.is_empty() {
+                evaluation.properties.remove(key);
+            } else {
+                evaluation.properties.insert(key.clone(), value);
+            }
+        }
+        AssignKey::Attr(name) => {
+            evaluation.attributes.insert(name.clone(), value);
+        }
+        AssignKey::Symlink => update_list(&mut evaluation.symlinks, assignment.op, &value),
+        AssignKey::Tag => update_list(&mut evaluation.tags, assignment.op, &value),
+        AssignKey::Run => match assignment.op {
+            AssignOp::Set | AssignOp::Final => evaluation.run = vec![value],
+            AssignOp::Add => evaluation.run.push(value),
+            AssignOp::Remove => evaluation.run.retain(|r| *r != value),
+        },
+        AssignKey::Name => evaluation.name = Some(value),
+        AssignKey::Owner => evaluation.owner = Some(value),
+        AssignKey::Group => evaluation.group = Some(value),
+        AssignKey::Mode => evaluation.mode = Some(value),
+    }
+}
+
+/// Returns the device and its parents, stopping at the top of the `devices` tree.
+fn device_chain(dir: &Path) -> Vec<RuleDevice> {
+    dir.ancestors()
+        .take_while(|d| d.file_name().is_some_and(|n| n != "devices"))
+        .filter(|d| d.join("uevent").is_file())
+        .map(RuleDevice::new)
+        .collect()
+}
+
+pub(crate) fn evaluate_udev_rules(rules: &UdevRules, dir: &Path) -> Result<UdevEvaluation, Error> {
+    let dir = fs::canonicalize(dir).map_err(|_| Error::NotFound)?;
+
+    if !dir.join("uevent").is_file() {
+        return Err(Error::NotFound);
+    }
+
+    let devices = device_chain(&dir);
+
+    let mut properties: BTreeMap<String, String> = sysfs::read_uevent(&dir);
+
+    properties.insert("ACTION".to_owned(), "add".to_owned());
+
+    if let Some(devpath) = sysfs::devpath(&dir) {
+        properties.insert("DEVPATH".to_owned(), devpath);
+    }
+
+    if let Some(subsystem) = &devices[0].subsystem {
+        properties.insert("SUBSYSTEM".to_owned(), subsystem.clone());
+    }
+
+    if let Some(devname) = properties.get_mut("DEVNAME") {
+        if !devname.starts_with('/') {
+            *devname = format!("/dev/{devname}");
+        }
+    }
+
+    let mut state = State::default();
+    state.evaluation.properties = properties;
+
+    let mut i = 0;
+
+    while let Some(rule) = rules.rules.get(i) {
+        i += 1;
+
+        let is_label_only =
+            rule.matches.is_empty() && rule.assignments.is_empty() && rule.goto.is_none();
+
+        if is_label_only || !rule_matches(rule, &devices, &state) {
+            continue;
+        }
+
+        state.evaluation.matched_lines.push(rule.line);
+
+        for assignment in &rule.assignments {
+            apply(assignment, &devices, &mut state);
+        }
+
+        if let Some(goto) = &rule.goto {
+            // Jumping to a missing label ends processing, as in udev
+            i = rules.rules[i..]
+                .iter()
+                .position(|r| r.label.as_ref() == Some(goto))
+                .map_or(rules.rules.len(), |j| i + j);
+        }
+    }
+
+    Ok(state.evaluation)
+}
+
+/// Returns the name of the udev database file of a device.
+///
+/// Devices with a device node are named by its type and number, such as `c189:1` or `b8:0`,
+/// network interfaces by their index, such as `n2`, and others by subsystem and name,
+/// such as `+pci:0000:00:1f.3`.
+pub(super) fn udev_data_id(dir: &Path) -> Option<String> {
+    let uevent = sysfs::read_uevent(dir);
+    let subsystem = sysfs::subsystem(dir)?;
+
+    if let Some((major, minor)) = uevent.get("MAJOR").zip(uevent.get("MINOR")) {
+        let kind = if subsystem == "block" { 'b' } else { 'c' };
+
+        return Some(format!("{kind}{major}:{minor}"));
+    }
+
+    if let Some(index) = uevent.get("IFINDEX") {
+        return Some(format!("n{index}"));
+    }
+
+    let name = dir.file_name()?.to_string_lossy();
+
+    Some(format!("+{subsystem}:{name}"))
+}
+
+pub(crate) fn read_udev_data(dir: &Path) -> Result<UdevData, Error> {
+    let dir = fs::canonicalize(dir).map_err(|_| Error::NotFound)?;
+    let id = udev_data_id(&dir).ok_or(Error::NotFound)?;
+
+    let contents = fs::read_to_string(fixture::system_path(UDEV_DATA_DIR).join(id))
+        .map_err(|_| Error::NotFound)?;
+
+    UdevData::parse(&contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::symlink;
+
+    use super::*;
+
+    /// Creates a device directory with a `uevent` file, a subsystem and attributes.
+    fn device(dir: &Path, subsystem: &str, uevent: &str, attrs: &[(&str, &str)]) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("uevent"), uevent).unwrap();
+        symlink(format!("/sys/bus/{subsystem}"), dir.join("subsystem")).unwrap();
+
+        for (name, value) in attrs {
+            fs::write(dir.join(name), format!("{value}\n")).unwrap();
+        }
+    }
+
+    /// A USB receiver behind a root hub, with sysfs copied under a temporary directory.
+    fn tree(name: &str) -> (PathBuf, PathBuf) {
+        let root = std::env::temp_dir().join(format!("devices-udev-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let pci = root.join("sys/devices/pci0000:00/0000:00:14.0");
+        let hub = pci.join("usb1");
+        let receiver = hub.join("1-2");
+
+        device(&pci, "pci", "DRIVER=xhci_hcd\n", &[("vendor", "0x8086")]);
+        device(&hub, "usb", "DEVTYPE=usb_device\n", &[("idVendor", "1d6b"), ("idProduct", "0002")]);
+        device(
+            &receiver,
+            "usb",
+            "DEVTYPE=usb_device\nDEVNAME=bus/usb/001/002\n",
+            &[("idVendor", "046d"), ("idProduct", "c52b"), ("manufacturer", "Logitech")],
+        );
+        device(
+            &receiver.join("1-2:1.0"),
+            "usb",
+            "DEVTYPE=usb_interface\n",
+            &[("bInterfaceClass", "03")],
+        );
+        symlink("/sys/bus/usb/drivers/usb", receiver.join("driver")).unwrap();
+
+        (root, receiver.join("1-2:1.0"))
+    }
+
+    fn rules(s: &str) -> UdevRules {
+        UdevRules::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parent_keys_match_the_same_ancestor() {
+        let (root, interface) = tree("ancestor");
+
+        let evaluation = rules(concat!(
+            "ATTRS{idVendor}==\"046d\", ATTRS{idProduct}==\"c52b\", TAG+=\"uaccess\"\n",
+            "ATTRS{idVendor}==\"046d\", ATTRS{idProduct}==\"0002\", ENV{MIXED}=\"1\"\n",
+            "KERNELS==\"1-2\", DRIVERS==\"usb\", ENV{VENDOR}=\"$attr{manufacturer}\"\n",
+            "ATTRS{serial}==\"*\", ENV{SERIAL}=\"1\"\n",
+            "ATTRS{serial}!=\"*\", ENV{NO_SERIAL}=\"1\"\n",
+        ))
+        .evaluate(&interface)
+        .unwrap();
+
+        assert_eq!(evaluation.matched_lines(), [1, 3]);
+        assert_eq!(evaluation.tags(), ["uaccess"]);
+        assert_eq!(evaluation.properties().get("VENDOR").map(String::as_str), Some("Logitech"));
+        assert!(!evaluation.properties().contains_key("MIXED"));
+        assert!(!evaluation.properties().contains_key("SERIAL"));
+        assert!(!evaluation.properties().contains_key("NO_SERIAL"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn applies_assignments_in_order() {
+        let (root, interface) = tree("assign");
+        let receiver = interface.parent().unwrap();
+
+        let rules = rules(concat!(
+            "SUBSYSTEM!=\"usb\", GOTO=\"usb_end\"\n",
+            "ENV{DEVTYPE}==\"usb_device\", SYMLINK+=\"receiver-%k\", SYMLINK+=\"logitech/%n\"\n",
+            "MODE:=\"0660\"\n",
+            "MODE=\"0666\", GROUP=\"plugdev\"\n",
+            "ENV{DEVTYPE}==\"usb_interface\", GOTO=\"usb_end\"\n",
+            "ENV{SKIPPED}=\"1\"\n",
+            "LABEL=\"usb_end\"\n",
+            "RUN+=\"/bin/echo $kernel %N\"\n",
+        ));
+
+        let evaluation = rules.evaluate(receiver).unwrap();
+
+        assert_eq!(evaluation.matched_lines(), [2, 3, 4, 6, 8]);
+        assert_eq!(evaluation.symlinks(), ["receiver-1-2", "logitech/2"]);
+        assert_eq!(evaluation.mode(), Some("0660"));
+        assert_eq!(evaluation.group(), Some("plugdev"));
+        assert_eq!(evaluation.properties().get("SKIPPED").map(String::as_str), Some("1"));
+        assert_eq!(evaluation.run(), ["/bin/echo 1-2 /dev/bus/usb/001/002"]);
+        assert_eq!(
+            evaluation.properties().get("DEVPATH").map(String::as_str),
+            Some("/devices/pci0000:00/0000:00:14.0/usb1/1-2")
+        );
+
+        let evaluation = rules.evaluate(&interface).unwrap();
+
+        assert_eq!(evaluation.matched_lines(), [3, 4, 5, 8]);
+        assert!(!evaluation.properties().contains_key("SKIPPED"));
+
+        let evaluation = rules.evaluate(&root.join("sys/devices/pci0000:00/0000:00:14.0")).unwrap();
+
+        assert_eq!(evaluation.matched_lines(), [1, 8]);
+        assert_eq!(evaluation.run(), ["/bin/echo 0000:00:14.0 %N"]);
+
+        assert!(matches!(
+            rules.evaluate(&root.join("sys/devices/pci0000:00")),
+            Err(Error::NotFound)
+        ));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
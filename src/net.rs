@@ -0,0 +1,77 @@
+use crate::info::DeviceInfo;
+
+/// Operational state of a network interface, as defined by RFC 2863.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum OperState {
+    /// The interface is up and able to pass packets.
+    Up,
+
+    /// The interface is down.
+    Down,
+
+    /// The interface is waiting for an external event.
+    Dormant,
+
+    /// The interface is down because a lower layer is down.
+    LowerLayerDown,
+
+    /// A component of the interface is missing.
+    NotPresent,
+
+    /// The interface is in test mode.
+    Testing,
+
+    /// The state could not be determined.
+    Unknown,
+}
+
+/// A network interface and the device that provides it.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NetworkAdapter {
+    pub(crate) device: DeviceInfo,
+
+    pub(crate) interface: String,
+    pub(crate) mac_address: Option<String>,
+    pub(crate) permanent_mac_address: Option<String>,
+    pub(crate) operstate: OperState,
+    pub(crate) speed: Option<u32>,
+}
+
+impl NetworkAdapter {
+    /// Returns the device that provides the interface.
+    pub fn device(&self) -> &DeviceInfo {
+        &self.device
+    }
+
+    /// Returns the name of the interface, such as `eth0` or `enp3s0`.
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    /// Returns the current hardware address of the interface, if known.
+    pub fn mac_address(&self) -> Option<&str> {
+        self.mac_address.as_deref()
+    }
+
+    /// Returns the permanent hardware address of the interface, if known.
+    /// # Note
+    /// On Linux this is read with the `ETHTOOL_GPERMADDR` ioctl. Drivers without ethtool support,
+    /// and fixtures being replayed, only know it when the current address has not been changed.
+    pub fn permanent_mac_address(&self) -> Option<&str> {
+        self.permanent_mac_address.as_deref()
+    }
+
+    /// Returns the operational state of the interface.
+    pub fn operstate(&self) -> OperState {
+        self.operstate
+    }
+
+    /// Returns the link speed in Mbit/s, if the link is up and the speed is known.
+    pub fn speed(&self) -> Option<u32> {
+        self.speed
+    }
+}
@@ -0,0 +1,33 @@
+/// A device node or network interface belonging to a device.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DeviceNode {
+    pub(crate) subsystem: String,
+    pub(crate) name: String,
+    pub(crate) devnode: Option<String>,
+}
+
+impl DeviceNode {
+    /// Returns the subsystem the node belongs to, such as `tty`, `hidraw` or `net`.
+    pub fn subsystem(&self) -> &str {
+        &self.subsystem
+    }
+
+    /// Returns the kernel name of the node, such as `ttyUSB0` or `eth0`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the device file, such as `/dev/ttyUSB0`.
+    /// # Note
+    /// Always returns `None` for network interfaces.
+    pub fn devnode(&self) -> Option<&str> {
+        self.devnode.as_deref()
+    }
+
+    /// Returns `true` if the node is a network interface.
+    pub fn is_net_interface(&self) -> bool {
+        self.subsystem == "net"
+    }
+}
@@ -1,10 +1,31 @@
+use std::{
+    fmt,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+};
+
+use cfg_if::cfg_if;
+
+use crate::error::{
+    Error,
+    ParsePathError,
+};
+
 /// Device mount path.
 #[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum DevicePath {
     /// A PCI device path.
     PCI {
+        /// PCI domain, also known as segment.
+        ///
+        /// `0` on most systems. Always `0` on Windows.
+        domain: u32,
+
         /// PCI bus id.
         bus: u8,
 
@@ -19,14 +40,444 @@ pub enum DevicePath {
 
     /// A USB device path.
     USB {
-        /// USB bus id.
+        /// USB bus id, the number of the root hub.
         ///
         /// Also known as `hub` on Windows.
         bus: u8,
 
-        /// USB device id.
+        /// Port numbers from the root hub down to the device, such as `[2, 3]` for port `3`
+        /// of the hub on port `2`. Empty for root hubs.
         ///
-        /// Also known as `port` on Windows.
-        device: u8,
+        /// Unlike device numbers, ports stay the same when a device is reconnected.
+        /// On Windows, this is the port of the device on its hub.
+        ports: Vec<u8>,
+    },
+
+    /// A platform device path.
+    Platform {
+        /// Kernel device name, such as `serial8250` or `3f804000.i2c`.
+        name: String,
+    },
+
+    /// An I2C device path.
+    I2C {
+        /// Kernel device name, such as `1-0050` or `i2c-ELAN0000:00`.
+        name: String,
     },
+
+    /// An SPI device path.
+    SPI {
+        /// Kernel device name, such as `spi0.1`.
+        name: String,
+    },
+
+    /// An SDIO function path.
+    SDIO {
+        /// Kernel device name, such as `mmc1:0001:1`.
+        name: String,
+    },
+
+    /// A Thunderbolt or USB4 router path.
+    Thunderbolt {
+        /// Kernel device name, such as `0-1`.
+        name: String,
+    },
+}
+
+impl DevicePath {
+    /// Returns the name of the bus the device is on, such as `pci` or `usb`.
+    ///
+    /// This is the prefix of the canonical string form.
+    pub fn bus(&self) -> &'static str {
+        match self {
+            Self::PCI { .. } => "pci",
+            Self::USB { .. } => "usb",
+            Self::Platform { .. } => "platform",
+            Self::I2C { .. } => "i2c",
+            Self::SPI { .. } => "spi",
+            Self::SDIO { .. } => "sdio",
+            Self::Thunderbolt { .. } => "thunderbolt",
+        }
+    }
+
+    /// Parses a PCI address as printed by `lspci`, such as `00:1f.3` or `0000:00:1f.3`.
+    ///
+    /// The domain defaults to `0` if omitted.
+    /// # Errors
+    /// If the address is malformed, an error is returned.
+    pub fn from_lspci(s: &str) -> Result<Self, ParsePathError> {
+        let invalid = || ParsePathError::InvalidPci(s.to_owned());
+
+        let (address, function) = s.trim().rsplit_once('.').ok_or_else(invalid)?;
+        let mut address = address.rsplit(':');
+
+        let slot = address.next().ok_or_else(invalid)?;
+        let bus = address.next().ok_or_else(invalid)?;
+
+        let domain = match address.next() {
+            Some(domain) => u32::from_str_radix(domain, 16).map_err(|_| invalid())?,
+            None => 0,
+        };
+
+        if address.next().is_some() {
+            return Err(invalid());
+        }
+
+        let bus = u8::from_str_radix(bus, 16).map_err(|_| invalid())?;
+        let slot = u8::from_str_radix(slot, 16).map_err(|_| invalid())?;
+        let function = u8::from_str_radix(function, 16).map_err(|_| invalid())?;
+
+        if slot > 0x1f || function > 7 {
+            return Err(invalid());
+        }
+
+        Ok(Self::PCI {
+            domain,
+            bus,
+            slot,
+            function,
+        })
+    }
+
+    /// Returns the address of a PCI device as printed by `lspci`, such as `00:1f.3`,
+    /// or `10000:00:1f.3` for devices outside domain `0`.
+    ///
+    /// Returns `None` for devices on other buses.
+    pub fn to_lspci(&self) -> Option<String> {
+        match self {
+            Self::PCI {
+                domain: 0,
+                bus,
+                slot,
+                function,
+            } => Some(format!("{bus:02x}:{slot:02x}.{function:x}")),
+            Self::PCI {
+                domain,
+                bus,
+                slot,
+                function,
+            } => Some(format!("{domain:04x}:{bus:02x}:{slot:02x}.{function:x}")),
+            _ => None,
+        }
+    }
+
+    /// Parses a USB port path as used by the Linux kernel, such as `1-2.3`, or `usb1` for a root hub.
+    /// # Errors
+    /// If the port path is malformed, an error is returned.
+    pub fn from_usb_port_path(s: &str) -> Result<Self, ParsePathError> {
+        let invalid = || ParsePathError::InvalidUsb(s.to_owned());
+
+        let number = |n: &str| n.parse::<u8>().ok().filter(|&n| n > 0).ok_or_else(invalid);
+
+        if let Some(bus) = s.strip_prefix("usb") {
+            return Ok(Self::USB {
+                bus: number(bus)?,
+                ports: Vec::new(),
+            });
+        }
+
+        let (bus, ports) = s.split_once('-').ok_or_else(invalid)?;
+
+        Ok(Self::USB {
+            bus: number(bus)?,
+            ports: ports.split('.').map(number).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Returns the USB port path of a USB device, such as `1-2.3`, or `usb1` for a root hub.
+    ///
+    /// This is the name of the device in `/sys/bus/usb/devices`.
+    ///
+    /// Returns `None` for devices on other buses.
+    pub fn to_usb_port_path(&self) -> Option<String> {
+        match self {
+            Self::USB { bus, ports } if ports.is_empty() => Some(format!("usb{bus}")),
+            Self::USB { bus, ports } => {
+                let ports: Vec<String> = ports.iter().map(ToString::to_string).collect();
+
+                Some(format!("{bus}-{}", ports.join(".")))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the path of a USB device listed by `lsusb`, such as `Bus 001 Device 002`.
+    ///
+    /// Anything following the device number, such as `: ID 046d:c52b`, is ignored.
+    /// As device numbers change whenever a device is reconnected, they are resolved to
+    /// the port the device is currently connected to.
+    /// # Errors
+    /// If the platform is unsupported, the location is malformed, or no such device is connected,
+    /// an error is returned.
+    pub fn from_lsusb(s: &str) -> Result<Self, Error> {
+        let mut words = s.split_whitespace();
+
+        if words.next() != Some("Bus") {
+            return Err(Error::ParseError);
+        }
+
+        let bus = words.next().and_then(|b| b.parse::<u8>().ok());
+
+        if words.next() != Some("Device") {
+            return Err(Error::ParseError);
+        }
+
+        let device = words
+            .next()
+            .map(|d| d.trim_end_matches(':'))
+            .and_then(|d| d.parse::<u8>().ok());
+
+        let (bus, device) = bus.zip(device).ok_or(Error::ParseError)?;
+
+        cfg_if! {
+            if #[cfg(unix)] {
+                crate::linux::get_usb_path(bus, device)
+            } else {
+                let _ = (bus, device);
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Returns the location of a connected USB device as printed by `lsusb`, such as `Bus 001 Device 002`.
+    /// # Errors
+    /// If the platform is unsupported, the device is not on the USB bus, or it is not connected,
+    /// an error is returned.
+    pub fn to_lsusb(&self) -> Result<String, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                let (bus, device) = crate::linux::get_usb_address(self)?;
+
+                Ok(format!("Bus {bus:03} Device {device:03}"))
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Parses a Windows location string, such as `PCI bus 0, device 31, function 3`
+    /// or `Port_#0002.Hub_#0001`.
+    /// # Errors
+    /// If the location is malformed, an error is returned.
+    pub fn from_windows_location(s: &str) -> Result<Self, ParsePathError> {
+        if let Some(rest) = s.strip_prefix("PCI bus ") {
+            let invalid = || ParsePathError::InvalidPci(s.to_owned());
+
+            let (bus, rest) = rest.split_once(", device ").ok_or_else(invalid)?;
+            let (slot, function) = rest.split_once(", function ").ok_or_else(invalid)?;
+
+            let bus = bus.parse().map_err(|_| invalid())?;
+            let slot = slot.parse::<u8>().map_err(|_| invalid())?;
+            let function = function.parse::<u8>().map_err(|_| invalid())?;
+
+            if slot > 0x1f || function > 7 {
+                return Err(invalid());
+            }
+
+            return Ok(Self::PCI {
+                domain: 0,
+                bus,
+                slot,
+                function,
+            });
+        }
+
+        let invalid = || ParsePathError::InvalidUsb(s.to_owned());
+
+        let (port, hub) = s.split_once('.').ok_or_else(invalid)?;
+        let port = port.strip_prefix("Port_#").ok_or_else(invalid)?;
+        let hub = hub.strip_prefix("Hub_#").ok_or_else(invalid)?;
+
+        Ok(Self::USB {
+            bus: u8::from_str_radix(hub, 16).map_err(|_| invalid())?,
+            ports: vec![u8::from_str_radix(port, 16).map_err(|_| invalid())?],
+        })
+    }
+
+    /// Returns the Windows location string of a PCI or USB device,
+    /// such as `PCI bus 0, device 31, function 3` or `Port_#0002.Hub_#0001`.
+    ///
+    /// Returns `None` for devices on other buses, PCI devices outside domain `0`,
+    /// and USB devices not identified by a single port.
+    pub fn to_windows_location(&self) -> Option<String> {
+        match self {
+            Self::PCI {
+                domain: 0,
+                bus,
+                slot,
+                function,
+            } => Some(format!("PCI bus {bus}, device {slot}, function {function}")),
+            Self::USB { bus, ports } => match ports[..] {
+                [port] => Some(format!("Port_#{port:04x}.Hub_#{bus:04x}")),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the sysfs directory of the device, such as `/sys/bus/pci/devices/0000:00:1f.3`.
+    /// # Errors
+    /// If the platform is unsupported or the device does not exist, an error is returned.
+    pub fn to_sysfs(&self) -> Result<PathBuf, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                crate::linux::get_sysfs_dir(self)
+            } else {
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Returns the path of the device owning a sysfs directory, such as
+    /// `/sys/bus/usb/devices/1-2.3` or `/sys/class/net/eth0`.
+    /// # Errors
+    /// If the platform is unsupported or the directory does not belong to a device, an error is returned.
+    pub fn from_sysfs(dir: &Path) -> Result<Self, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                crate::linux::get_sysfs_owner(dir)
+            } else {
+                let _ = dir;
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+}
+
+impl fmt::Display for DevicePath {
+    /// Formats the path in its canonical form, such as `pci:0000:00:1f.3`, `usb:1-2.3`
+    /// or `platform:serial8250`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PCI {
+                domain,
+                bus,
+                slot,
+                function,
+            } => write!(f, "pci:{domain:04x}:{bus:02x}:{slot:02x}.{function:x}"),
+            Self::USB { .. } => {
+                write!(f, "usb:{}", self.to_usb_port_path().unwrap_or_default())
+            }
+            Self::Platform { name }
+            | Self::I2C { name }
+            | Self::SPI { name }
+            | Self::SDIO { name }
+            | Self::Thunderbolt { name } => write!(f, "{}:{name}", self.bus()),
+        }
+    }
+}
+
+impl FromStr for DevicePath {
+    type Err = ParsePathError;
+
+    /// Parses a path in its canonical form.
+    ///
+    /// The PCI domain may be omitted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (bus, address) = s.split_once(':').ok_or(ParsePathError::MissingBus)?;
+
+        let name = || {
+            if address.is_empty() {
+                Err(ParsePathError::EmptyName)
+            } else {
+                Ok(address.to_owned())
+            }
+        };
+
+        match bus {
+            "pci" => Self::from_lspci(address),
+            // Bus and device numbers, such as `001:002`, were the canonical form before 0.6
+            "usb" if address.contains(':') => Err(ParsePathError::UsbAddress(address.to_owned())),
+            "usb" => Self::from_usb_port_path(address),
+            "platform" => Ok(Self::Platform { name: name()? }),
+            "i2c" => Ok(Self::I2C { name: name()? }),
+            "spi" => Ok(Self::SPI { name: name()? }),
+            "sdio" => Ok(Self::SDIO { name: name()? }),
+            "thunderbolt" => Ok(Self::Thunderbolt { name: name()? }),
+            _ => Err(ParsePathError::UnknownBus(bus.to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pci(domain: u32, bus: u8, slot: u8, function: u8) -> DevicePath {
+        DevicePath::PCI {
+            domain,
+            bus,
+            slot,
+            function,
+        }
+    }
+
+    fn usb(bus: u8, ports: &[u8]) -> DevicePath {
+        DevicePath::USB {
+            bus,
+            ports: ports.to_vec(),
+        }
+    }
+
+    #[test]
+    fn canonical_form_round_trips() {
+        let paths = [
+            (pci(0, 0, 0x1f, 3), "pci:0000:00:1f.3"),
+            (pci(0x10000, 0xe1, 0, 0), "pci:10000:e1:00.0"),
+            (usb(1, &[]), "usb:usb1"),
+            (usb(1, &[2, 3]), "usb:1-2.3"),
+            (usb(3, &[10]), "usb:3-10"),
+            (
+                DevicePath::Platform {
+                    name: "serial8250".to_owned(),
+                },
+                "platform:serial8250",
+            ),
+            (
+                DevicePath::I2C {
+                    name: "i2c-ELAN0000:00".to_owned(),
+                },
+                "i2c:i2c-ELAN0000:00",
+            ),
+        ];
+
+        for (path, s) in paths {
+            assert_eq!(path.to_string(), s);
+            assert_eq!(s.parse::<DevicePath>().unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn parses_pci_addresses() {
+        assert_eq!("pci:00:1f.3".parse(), Ok(pci(0, 0, 0x1f, 3)));
+        assert_eq!(DevicePath::from_lspci("0001:03:00.1"), Ok(pci(1, 3, 0, 1)));
+        assert_eq!(pci(1, 3, 0, 1).to_lspci().as_deref(), Some("0001:03:00.1"));
+        assert_eq!(pci(0, 3, 0, 1).to_lspci().as_deref(), Some("03:00.1"));
+
+        for invalid in ["00:20.0", "00:1f.8", "0:0:00:1f.3", "00.0", "xx:00.0"] {
+            assert!(DevicePath::from_lspci(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn rejects_usb_device_numbers() {
+        assert_eq!(
+            "usb:001:002".parse::<DevicePath>(),
+            Err(ParsePathError::UsbAddress("001:002".to_owned()))
+        );
+
+        for invalid in ["usb:1-0", "usb:0-1", "usb:1-", "usb:1-2..3", "usb:usb", "usb:1"] {
+            assert!(invalid.parse::<DevicePath>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn converts_windows_locations() {
+        let path = DevicePath::from_windows_location("Port_#0002.Hub_#0001").unwrap();
+
+        assert_eq!(path, usb(1, &[2]));
+        assert_eq!(path.to_windows_location().as_deref(), Some("Port_#0002.Hub_#0001"));
+        assert_eq!(usb(1, &[2, 3]).to_windows_location(), None);
+        assert_eq!(pci(1, 0, 0, 0).to_windows_location(), None);
+    }
 }
@@ -0,0 +1,104 @@
+/// PCI device power state.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DevicePowerState {
+    /// Fully powered.
+    D0,
+
+    /// Intermediate power saving state.
+    D1,
+
+    /// Intermediate power saving state.
+    D2,
+
+    /// Powered off, auxiliary power present.
+    D3Hot,
+
+    /// Powered off completely.
+    D3Cold,
+}
+
+/// Runtime power management policy.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PowerControl {
+    /// The device may be suspended at runtime.
+    Auto,
+
+    /// The device is kept powered at all times.
+    On,
+}
+
+/// Runtime power management status.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RuntimeStatus {
+    /// The device is active.
+    Active,
+
+    /// The device is suspended.
+    Suspended,
+
+    /// The device is being suspended.
+    Suspending,
+
+    /// The device is being resumed.
+    Resuming,
+
+    /// Runtime power management has failed.
+    Error,
+
+    /// Runtime power management is not enabled for the device.
+    Unsupported,
+}
+
+/// Power management state of a device.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PowerState {
+    pub(crate) device_state: Option<DevicePowerState>,
+    pub(crate) control: Option<PowerControl>,
+    pub(crate) runtime_status: Option<RuntimeStatus>,
+    pub(crate) autosuspend_delay_ms: Option<i32>,
+    pub(crate) wakeup: Option<bool>,
+}
+
+impl PowerState {
+    /// Returns the device power state, if known.
+    /// # Note
+    /// Always returns `None` for USB devices.
+    pub fn device_state(&self) -> Option<DevicePowerState> {
+        self.device_state
+    }
+
+    /// Returns the runtime power management policy, if known.
+    pub fn control(&self) -> Option<PowerControl> {
+        self.control
+    }
+
+    /// Returns the runtime power management status, if known.
+    pub fn runtime_status(&self) -> Option<RuntimeStatus> {
+        self.runtime_status
+    }
+
+    /// Returns the autosuspend delay in milliseconds, if supported.
+    ///
+    /// A negative delay prevents the device from being autosuspended.
+    pub fn autosuspend_delay_ms(&self) -> Option<i32> {
+        self.autosuspend_delay_ms
+    }
+
+    /// Returns `true` if the device is capable of waking the system.
+    pub fn wakeup_capable(&self) -> bool {
+        self.wakeup.is_some()
+    }
+
+    /// Returns `true` if the device is allowed to wake the system.
+    pub fn wakeup_enabled(&self) -> bool {
+        self.wakeup == Some(true)
+    }
+}
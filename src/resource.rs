@@ -0,0 +1,70 @@
+const IORESOURCE_IO: u64 = 0x0000_0100;
+const IORESOURCE_MEM: u64 = 0x0000_0200;
+const IORESOURCE_PREFETCH: u64 = 0x0000_2000;
+const IORESOURCE_MEM_64: u64 = 0x0010_0000;
+
+/// The index of the expansion ROM resource of a PCI device.
+pub const PCI_ROM_RESOURCE: u8 = 6;
+
+/// A memory or I/O window of a PCI device.
+///
+/// Indices `0` to `5` are Base Address Registers (BARs), index [`PCI_ROM_RESOURCE`] is the expansion ROM,
+/// and higher indices are bridge windows.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PciResource {
+    pub(crate) index: u8,
+    pub(crate) start: u64,
+    pub(crate) size: u64,
+    pub(crate) flags: u64,
+}
+
+impl PciResource {
+    /// Returns the index of the resource.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the base address of the resource.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the last address of the resource, saturating at `u64::MAX`.
+    pub fn end(&self) -> u64 {
+        self.start.saturating_add(self.size.saturating_sub(1))
+    }
+
+    /// Returns the size of the resource in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the raw resource flags.
+    /// # Note
+    /// On Linux these are the kernel's `IORESOURCE_*` flags.
+    pub fn flags(&self) -> u64 {
+        self.flags
+    }
+
+    /// Returns `true` if the resource is an I/O port window.
+    pub fn is_io(&self) -> bool {
+        self.flags & IORESOURCE_IO != 0
+    }
+
+    /// Returns `true` if the resource is a memory window.
+    pub fn is_memory(&self) -> bool {
+        self.flags & IORESOURCE_MEM != 0
+    }
+
+    /// Returns `true` if the resource is prefetchable memory.
+    pub fn is_prefetchable(&self) -> bool {
+        self.flags & IORESOURCE_PREFETCH != 0
+    }
+
+    /// Returns `true` if the resource is 64-bit memory.
+    pub fn is_64bit(&self) -> bool {
+        self.flags & IORESOURCE_MEM_64 != 0
+    }
+}
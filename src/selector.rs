@@ -0,0 +1,920 @@
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+use crate::{
+    error::{
+        ParseSelectorError,
+        SelectorErrorKind,
+    },
+    glob::glob_match,
+    DeviceInfo,
+};
+
+const BUSES: [&str; 7] = ["pci", "usb", "platform", "i2c", "spi", "sdio", "thunderbolt"];
+
+/// A device selection expression, such as `usb vid=046d pid=c52b`.
+///
+/// A selector is made of terms:
+/// - a bus name, such as `pci` or `usb`
+/// - `key=value` or `key!=value`, where `key` is one of:
+///   - `bus`: the bus name
+///   - `vid`, `pid`: the vendor and product ids, in hex with an optional `0x` prefix
+///   - `class`: the class id if the value has a `0x` prefix or is four hex digits, or the class name otherwise
+///   - `class_id`: the class id
+///   - `vendor`, `product`, `manufacturer`, `serial`: names and the serial number
+///   - `path`: the canonical path, such as `pci:0000:00:1f.3`
+///   - `slot`: the PCI address as printed by `lspci`, such as `03:00.0`
+///   - `compatible`: any of the device tree `compatible` strings
+///   - `hid`: the ACPI hardware id
+///   - `numa`: the NUMA node
+///
+/// Values may contain the wildcards `*`, `?` and `[...]`, and may be quoted with `"` if they contain spaces.
+/// Text is compared case-insensitively.
+///
+/// Terms are combined with `and` (or `&&`, or just whitespace), `or` (or `||`), `not` (or `!`)
+/// and parentheses. `not` binds tighter than `and`, which binds tighter than `or`.
+///
+/// ```
+/// # use devices::Selector;
+/// let selector: Selector = "pci class=0x0200 slot=03:00.* or usb vendor=\"Logitech*\"".parse().unwrap();
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Selector {
+    pub(crate) expr: Expr,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) enum Expr {
+    Term(Term),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) struct Term {
+    key: Key,
+    negated: bool,
+    value: String,
+    pattern: Pattern,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Key {
+    Bus,
+    VendorId,
+    ProductId,
+    Class,
+    ClassId,
+    Vendor,
+    Product,
+    Manufacturer,
+    Serial,
+    Path,
+    Slot,
+    Compatible,
+    AcpiHid,
+    NumaNode,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Pattern {
+    Id(u16),
+    Number(u16),
+    /// A lowercase glob pattern.
+    Glob(String),
+    /// A lowercase glob pattern over a hex id.
+    IdGlob(String),
+}
+
+impl Key {
+    fn parse(key: &str) -> Option<Self> {
+        Some(match key.to_ascii_lowercase().as_str() {
+            "bus" => Self::Bus,
+            "vid" | "vendor_id" => Self::VendorId,
+            "pid" | "product_id" => Self::ProductId,
+            "class" => Self::Class,
+            "class_id" => Self::ClassId,
+            "vendor" => Self::Vendor,
+            "product" => Self::Product,
+            "manufacturer" => Self::Manufacturer,
+            "serial" => Self::Serial,
+            "path" => Self::Path,
+            "slot" => Self::Slot,
+            "compatible" => Self::Compatible,
+            "hid" | "acpi_hid" => Self::AcpiHid,
+            "numa" | "numa_node" => Self::NumaNode,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bus => "bus",
+            Self::VendorId => "vid",
+            Self::ProductId => "pid",
+            Self::Class => "class",
+            Self::ClassId => "class_id",
+            Self::Vendor => "vendor",
+            Self::Product => "product",
+            Self::Manufacturer => "manufacturer",
+            Self::Serial => "serial",
+            Self::Path => "path",
+            Self::Slot => "slot",
+            Self::Compatible => "compatible",
+            Self::AcpiHid => "hid",
+            Self::NumaNode => "numa",
+        }
+    }
+}
+
+fn is_glob(value: &str) -> bool {
+    value.contains(['*', '?', '['])
+}
+
+fn parse_id(value: &str) -> Option<Pattern> {
+    let lower = value.to_ascii_lowercase();
+    let hex = lower.strip_prefix("0x").unwrap_or(&lower);
+
+    if hex.is_empty() {
+        None
+    } else if is_glob(hex) {
+        Some(Pattern::IdGlob(hex.to_owned()))
+    } else if hex.len() <= 4 {
+        u16::from_str_radix(hex, 16).ok().map(Pattern::Id)
+    } else {
+        None
+    }
+}
+
+impl Pattern {
+    fn parse(key: Key, value: &str) -> Result<Self, &'static str> {
+        match key {
+            Key::Bus => {
+                let bus = value.to_ascii_lowercase();
+
+                if is_glob(&bus) || BUSES.contains(&bus.as_str()) {
+                    Ok(Self::Glob(bus))
+                } else {
+                    Err("a bus name such as `pci` or `usb`")
+                }
+            }
+            Key::VendorId | Key::ProductId | Key::ClassId => {
+                parse_id(value).ok_or("a hex id such as `046d` or `0x046d`")
+            }
+            Key::Class => {
+                let is_id = value.starts_with("0x")
+                    || value.starts_with("0X")
+                    || (value.len() == 4 && value.bytes().all(|b| b.is_ascii_hexdigit()));
+
+                if is_id {
+                    parse_id(value).ok_or("a hex class id such as `0x0200`, or a class name")
+                } else {
+                    Ok(Self::Glob(value.to_lowercase()))
+                }
+            }
+            Key::Slot => {
+                let slot = value.to_ascii_lowercase();
+                let slot = slot.strip_prefix("0000:").unwrap_or(&slot);
+
+                Ok(Self::Glob(slot.to_owned()))
+            }
+            Key::NumaNode => value.parse().map(Self::Number).map_err(|_| "a NUMA node number"),
+            _ => Ok(Self::Glob(value.to_lowercase())),
+        }
+    }
+
+    fn matches_id(&self, id: Option<u16>) -> bool {
+        match (self, id) {
+            (Self::Id(pattern), Some(id)) => *pattern == id,
+            (Self::IdGlob(pattern), Some(id)) => glob_match(pattern, &format!("{id:04x}")),
+            _ => false,
+        }
+    }
+
+    fn matches_text(&self, text: Option<&str>) -> bool {
+        match (self, text) {
+            (Self::Glob(pattern), Some(text)) => glob_match(pattern, &text.to_lowercase()),
+            _ => false,
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, device: &DeviceInfo) -> bool {
+        let pattern = &self.pattern;
+
+        let matched = match self.key {
+            Key::Bus => pattern.matches_text(Some(device.path.bus())),
+            Key::VendorId => pattern.matches_id(Some(device.vendor_id)),
+            Key::ProductId => pattern.matches_id(Some(device.product_id)),
+            Key::ClassId => pattern.matches_id(device.class_id),
+            Key::Class => match pattern {
+                Pattern::Glob(_) => pattern.matches_text(Some(&device.class)),
+                _ => pattern.matches_id(device.class_id),
+            },
+            Key::Vendor => pattern.matches_text(Some(&device.vendor)),
+            Key::Product => pattern.matches_text(Some(&device.product)),
+            Key::Manufacturer => pattern.matches_text(device.manufacturer.as_deref()),
+            Key::Serial => pattern.matches_text(device.serial.as_deref()),
+            Key::Path => pattern.matches_text(Some(&device.path.to_string())),
+            Key::Slot => pattern.matches_text(device.path.to_lspci().as_deref()),
+            Key::Compatible => device
+                .compatible
+                .iter()
+                .any(|c| pattern.matches_text(Some(c))),
+            Key::AcpiHid => pattern.matches_text(device.acpi_hid.as_deref()),
+            Key::NumaNode => matches!(pattern, Pattern::Number(n) if device.numa_node == Some(*n)),
+        };
+
+        matched != self.negated
+    }
+}
+
+impl Expr {
+    fn matches(&self, device: &DeviceInfo) -> bool {
+        match self {
+            Self::Term(term) => term.matches(device),
+            Self::Not(expr) => !expr.matches(device),
+            Self::And(exprs) => exprs.iter().all(|e| e.matches(device)),
+            Self::Or(exprs) => exprs.iter().any(|e| e.matches(device)),
+        }
+    }
+}
+
+impl Selector {
+    /// Parses a selector expression.
+    /// # Errors
+    /// If the expression is malformed, an error describing the problem and its position is returned.
+    pub fn parse(s: &str) -> Result<Self, ParseSelectorError> {
+        let tokens = lex(s)?;
+
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: s.chars().count() + 1,
+        };
+
+        if parser.tokens.is_empty() {
+            return Err(ParseSelectorError {
+                column: 1,
+                kind: SelectorErrorKind::Empty,
+            });
+        }
+
+        let expr = parser.parse_or()?;
+
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(token.unexpected());
+        }
+
+        Ok(Self { expr })
+    }
+
+    /// Returns `true` if the device is selected.
+    pub fn matches(&self, device: &DeviceInfo) -> bool {
+        self.expr.matches(device)
+    }
+
+    /// Returns the selected devices.
+    pub fn select<'a>(&self, devices: &'a [DeviceInfo]) -> Vec<&'a DeviceInfo> {
+        devices.iter().filter(|d| self.matches(d)).collect()
+    }
+}
+
+impl FromStr for Selector {
+    type Err = ParseSelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+fn write_value(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    let plain = !value.is_empty()
+        && !value.contains(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '"' | '\\'));
+
+    if plain {
+        return f.write_str(value);
+    }
+
+    f.write_str("\"")?;
+
+    for c in value.chars() {
+        if matches!(c, '"' | '\\') {
+            f.write_str("\\")?;
+        }
+
+        write!(f, "{c}")?;
+    }
+
+    f.write_str("\"")
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write_nested = |f: &mut fmt::Formatter<'_>, expr: &Self, parens: bool| {
+            if parens {
+                write!(f, "({expr})")
+            } else {
+                write!(f, "{expr}")
+            }
+        };
+
+        match self {
+            Self::Term(term) => {
+                write!(f, "{}{}", term.key.name(), if term.negated { "!=" } else { "=" })?;
+                write_value(f, &term.value)
+            }
+            Self::Not(expr) => {
+                f.write_str("not ")?;
+                write_nested(f, expr, !matches!(**expr, Self::Term(_)))
+            }
+            Self::And(exprs) => {
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+
+                    write_nested(f, expr, matches!(expr, Self::Or(_)))?;
+                }
+
+                Ok(())
+            }
+            Self::Or(exprs) => {
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" or ")?;
+                    }
+
+                    write!(f, "{expr}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Selector {
+    /// Formats the selector in a normalized form that parses back to an equal selector.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expr.fmt(f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Selector {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Selector {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// Lexer
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Not,
+    And,
+    Or,
+    Word(String),
+    Op { negated: bool },
+    Value(String),
+}
+
+struct Spanned {
+    token: Token,
+    column: usize,
+}
+
+impl Spanned {
+    fn unexpected(&self) -> ParseSelectorError {
+        let text = match &self.token {
+            Token::LParen => "(".to_owned(),
+            Token::RParen => ")".to_owned(),
+            Token::Not => "not".to_owned(),
+            Token::And => "and".to_owned(),
+            Token::Or => "or".to_owned(),
+            Token::Word(w) | Token::Value(w) => w.clone(),
+            Token::Op { negated } => if *negated { "!=" } else { "=" }.to_owned(),
+        };
+
+        ParseSelectorError {
+            column: self.column,
+            kind: SelectorErrorKind::UnexpectedToken(text),
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '(' | ')' | '=' | '!' | '&' | '|' | '"')
+}
+
+fn lex(s: &str) -> Result<Vec<Spanned>, ParseSelectorError> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+
+        let token = match c {
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                i += 1;
+                Token::LParen
+            }
+            ')' => {
+                i += 1;
+                Token::RParen
+            }
+            '&' | '|' => {
+                i += if chars.get(i + 1) == Some(&c) { 2 } else { 1 };
+
+                if c == '&' {
+                    Token::And
+                } else {
+                    Token::Or
+                }
+            }
+            '!' | '=' => {
+                let negated = c == '!';
+
+                if chars.get(i + 1) == Some(&'=') {
+                    i += 2;
+                } else if negated {
+                    i += 1;
+                    tokens.push(Spanned {
+                        token: Token::Not,
+                        column,
+                    });
+                    continue;
+                } else {
+                    i += 1;
+                }
+
+                tokens.push(Spanned {
+                    token: Token::Op { negated },
+                    column,
+                });
+
+                // The value immediately follows the operator
+                let column = i + 1;
+
+                let value = if chars.get(i) == Some(&'"') {
+                    let (value, next) = lex_quoted(&chars, i)?;
+                    i = next;
+                    value
+                } else {
+                    let start = i;
+
+                    while i < chars.len() && !chars[i].is_whitespace() && chars[i] != ')' {
+                        i += 1;
+                    }
+
+                    if start == i {
+                        continue;
+                    }
+
+                    chars[start..i].iter().collect()
+                };
+
+                tokens.push(Spanned {
+                    token: Token::Value(value),
+                    column,
+                });
+                continue;
+            }
+            '"' => {
+                return Err(ParseSelectorError {
+                    column,
+                    kind: SelectorErrorKind::UnexpectedToken("\"".to_owned()),
+                });
+            }
+            _ => {
+                let start = i;
+
+                while i < chars.len() && is_word_char(chars[i]) {
+                    i += 1;
+                }
+
+                let word: String = chars[start..i].iter().collect();
+
+                match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Word(word),
+                }
+            }
+        };
+
+        tokens.push(Spanned { token, column });
+    }
+
+    Ok(tokens)
+}
+
+/// Lexes a quoted string starting at `start`, returning its contents and the index after it.
+fn lex_quoted(chars: &[char], start: usize) -> Result<(String, usize), ParseSelectorError> {
+    let mut value = String::new();
+    let mut i = start + 1;
+
+    while let Some(&c) = chars.get(i) {
+        match c {
+            '"' => return Ok((value, i + 1)),
+            '\\' => {
+                if let Some(&escaped) = chars.get(i + 1) {
+                    value.push(escaped);
+                }
+
+                i += 2;
+            }
+            _ => {
+                value.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    Err(ParseSelectorError {
+        column: start + 1,
+        kind: SelectorErrorKind::UnterminatedString,
+    })
+}
+
+// Parser
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn next(&mut self) -> Result<&Spanned, ParseSelectorError> {
+        let token = self.tokens.get(self.pos).ok_or(ParseSelectorError {
+            column: self.end,
+            kind: SelectorErrorKind::UnexpectedEnd,
+        })?;
+
+        self.pos += 1;
+
+        Ok(token)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseSelectorError> {
+        let mut exprs = Vec::new();
+
+        loop {
+            // Groups of the same kind are flattened, so that `(a or b) or c` equals `a or b or c`
+            match self.parse_and()? {
+                Expr::Or(nested) => exprs.extend(nested),
+                expr => exprs.push(expr),
+            }
+
+            if self.peek() != Some(&Token::Or) {
+                break;
+            }
+
+            self.pos += 1;
+        }
+
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::Or(exprs)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseSelectorError> {
+        let mut exprs = Vec::new();
+
+        loop {
+            match self.parse_unary()? {
+                Expr::And(nested) => exprs.extend(nested),
+                expr => exprs.push(expr),
+            }
+
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                Some(Token::Word(_) | Token::Not | Token::LParen) => {}
+                _ => break,
+            }
+        }
+
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::And(exprs)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ParseSelectorError> {
+        let token = self.next()?;
+        let column = token.column;
+
+        match &token.token {
+            Token::Not => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Token::LParen => {
+                let expr = self.parse_or()?;
+
+                match self.peek() {
+                    Some(Token::RParen) => {
+                        self.pos += 1;
+                        Ok(expr)
+                    }
+                    Some(_) => Err(self.tokens[self.pos].unexpected()),
+                    None => Err(ParseSelectorError {
+                        column,
+                        kind: SelectorErrorKind::UnclosedParen,
+                    }),
+                }
+            }
+            Token::Word(word) => {
+                let word = word.clone();
+                self.parse_term(word, column)
+            }
+            _ => Err(token.unexpected()),
+        }
+    }
+
+    fn parse_term(&mut self, word: String, column: usize) -> Result<Expr, ParseSelectorError> {
+        let error = |kind| ParseSelectorError { column, kind };
+
+        let Some(&Token::Op { negated }) = self.peek() else {
+            let bus = word.to_ascii_lowercase();
+
+            return if BUSES.contains(&bus.as_str()) {
+                Ok(Expr::Term(Term {
+                    key: Key::Bus,
+                    negated: false,
+                    pattern: Pattern::Glob(bus.clone()),
+                    value: bus,
+                }))
+            } else if Key::parse(&word).is_some() {
+                Err(error(SelectorErrorKind::MissingValue(word)))
+            } else {
+                Err(error(SelectorErrorKind::UnknownBus(word)))
+            };
+        };
+
+        self.pos += 1;
+
+        let key = Key::parse(&word)
+            .ok_or_else(|| error(SelectorErrorKind::UnknownKey(word.clone())))?;
+
+        let Some(Token::Value(value)) = self.peek() else {
+            return Err(error(SelectorErrorKind::MissingValue(word)));
+        };
+
+        let value = value.clone();
+        let value_column = self.tokens[self.pos].column;
+        self.pos += 1;
+
+        let pattern = Pattern::parse(key, &value).map_err(|expected| ParseSelectorError {
+            column: value_column,
+            kind: SelectorErrorKind::InvalidValue {
+                key: word,
+                value: value.clone(),
+                expected,
+            },
+        })?;
+
+        Ok(Expr::Term(Term {
+            key,
+            negated,
+            value,
+            pattern,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(s: &str) -> Vec<(Token, usize)> {
+        lex(s)
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.token, t.column))
+            .collect()
+    }
+
+    fn error(s: &str) -> (usize, SelectorErrorKind) {
+        let error = Selector::parse(s).unwrap_err();
+        (error.column(), error.kind().clone())
+    }
+
+    fn word(s: &str) -> Token {
+        Token::Word(s.to_owned())
+    }
+
+    fn value(s: &str) -> Token {
+        Token::Value(s.to_owned())
+    }
+
+    #[test]
+    fn lexes_operators_and_values() {
+        assert_eq!(
+            tokens("usb vid!=046d && (pid=c52b || !class=0x0200)"),
+            [
+                (word("usb"), 1),
+                (word("vid"), 5),
+                (Token::Op { negated: true }, 8),
+                (value("046d"), 10),
+                (Token::And, 15),
+                (Token::LParen, 18),
+                (word("pid"), 19),
+                (Token::Op { negated: false }, 22),
+                (value("c52b"), 23),
+                (Token::Or, 28),
+                (Token::Not, 31),
+                (word("class"), 32),
+                (Token::Op { negated: false }, 37),
+                (value("0x0200"), 38),
+                (Token::RParen, 44),
+            ]
+        );
+    }
+
+    #[test]
+    fn lexes_keywords_case_insensitively() {
+        assert_eq!(
+            tokens("NOT pci AND usb Or i2c"),
+            [
+                (Token::Not, 1),
+                (word("pci"), 5),
+                (Token::And, 9),
+                (word("usb"), 13),
+                (Token::Or, 17),
+                (word("i2c"), 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn lexes_quoted_values() {
+        assert_eq!(
+            tokens(r#"vendor="Logitech, Inc." product="say \"hi\" \\o/""#),
+            [
+                (word("vendor"), 1),
+                (Token::Op { negated: false }, 7),
+                (value("Logitech, Inc."), 8),
+                (word("product"), 25),
+                (Token::Op { negated: false }, 32),
+                (value(r#"say "hi" \o/"#), 33),
+            ]
+        );
+    }
+
+    #[test]
+    fn value_stops_at_closing_paren() {
+        assert_eq!(
+            tokens("(slot=03:00.*)"),
+            [
+                (Token::LParen, 1),
+                (word("slot"), 2),
+                (Token::Op { negated: false }, 6),
+                (value("03:00.*"), 7),
+                (Token::RParen, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and_than_or() {
+        let selector = Selector::parse("not pci usb or i2c").unwrap();
+
+        let Expr::Or(exprs) = &selector.expr else {
+            panic!("expected `or`, got {selector:?}");
+        };
+
+        assert_eq!(exprs.len(), 2);
+        assert!(matches!(
+            &exprs[0],
+            Expr::And(e) if matches!(e[..], [Expr::Not(_), Expr::Term(_)])
+        ));
+        assert!(matches!(exprs[1], Expr::Term(_)));
+    }
+
+    #[test]
+    fn whitespace_means_and() {
+        assert_eq!(
+            Selector::parse("usb vid=046d").unwrap(),
+            Selector::parse("usb && vid=046d").unwrap()
+        );
+    }
+
+    #[test]
+    fn flattens_nested_groups() {
+        assert_eq!(
+            Selector::parse("(usb vid=1 or pci) or i2c").unwrap(),
+            Selector::parse("usb vid=1 or pci or i2c").unwrap()
+        );
+        assert_eq!(
+            Selector::parse("usb (vid=1 pid=2)").unwrap(),
+            Selector::parse("usb vid=1 pid=2").unwrap()
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let selectors = [
+            "usb",
+            "(usb vid=1 or pci) or i2c",
+            "usb (vid=1 (pid=2 serial=x))",
+            "(pci or usb) (vid=8086 or vid=10de)",
+            "not (pci or usb) not vid=1",
+            "not not pci",
+            "!(usb && !(vid=046d || pid=c52b))",
+            r#"vendor="Logitech, Inc." product="a \"b\" (c)" serial!="""#,
+            "class=0x0200 class=Ethernet* path=pci:0000:00:1f.3 slot=0000:03:00.*",
+            "VID=046D numa_node=1 acpi_hid=PNP0A08 compatible=brcm,*",
+        ];
+
+        for s in selectors {
+            let selector = Selector::parse(s).unwrap();
+            let printed = selector.to_string();
+
+            assert_eq!(Selector::parse(&printed).unwrap(), selector, "{s} printed as {printed}");
+            assert_eq!(Selector::parse(&printed).unwrap().to_string(), printed);
+        }
+    }
+
+    #[test]
+    fn displays_normalized_form() {
+        let selector = Selector::parse("(USB || pci) && !VID=046d").unwrap();
+
+        assert_eq!(selector.to_string(), "(bus=usb or bus=pci) not vid=046d");
+    }
+
+    #[test]
+    fn reports_error_columns() {
+        assert_eq!(error(""), (1, SelectorErrorKind::Empty));
+        assert_eq!(error("   "), (1, SelectorErrorKind::Empty));
+        assert_eq!(error("usb or"), (7, SelectorErrorKind::UnexpectedEnd));
+        assert_eq!(error("usb )"), (5, SelectorErrorKind::UnexpectedToken(")".to_owned())));
+        assert_eq!(error("usb (pci"), (5, SelectorErrorKind::UnclosedParen));
+        assert_eq!(error("usb fish"), (5, SelectorErrorKind::UnknownBus("fish".to_owned())));
+        assert_eq!(error("pci size=1"), (5, SelectorErrorKind::UnknownKey("size".to_owned())));
+        assert_eq!(error("pci vid"), (5, SelectorErrorKind::MissingValue("vid".to_owned())));
+        assert_eq!(error("pci vid= pid=1"), (5, SelectorErrorKind::MissingValue("vid".to_owned())));
+        assert_eq!(error(r#"vendor="Intel"#), (8, SelectorErrorKind::UnterminatedString));
+        assert_eq!(error(r#"pci "x""#), (5, SelectorErrorKind::UnexpectedToken("\"".to_owned())));
+        assert_eq!(error("usb and or"), (9, SelectorErrorKind::UnexpectedToken("or".to_owned())));
+        assert_eq!(error("pci = 1"), (1, SelectorErrorKind::UnknownKey("pci".to_owned())));
+        assert_eq!(
+            error("usb vid=xyz"),
+            (
+                9,
+                SelectorErrorKind::InvalidValue {
+                    key: "vid".to_owned(),
+                    value: "xyz".to_owned(),
+                    expected: "a hex id such as `046d` or `0x046d`",
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        assert_eq!(
+            error("vendor=\"Müller\" fish"),
+            (17, SelectorErrorKind::UnknownBus("fish".to_owned()))
+        );
+        assert_eq!(
+            error("vendor=\"Müller\" vid=\"x\""),
+            (
+                21,
+                SelectorErrorKind::InvalidValue {
+                    key: "vid".to_owned(),
+                    value: "x".to_owned(),
+                    expected: "a hex id such as `046d` or `0x046d`",
+                }
+            )
+        );
+    }
+}
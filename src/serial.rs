@@ -0,0 +1,67 @@
+use crate::info::DeviceInfo;
+
+/// A serial port and the device that provides it.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SerialPort {
+    pub(crate) name: String,
+    pub(crate) device: Option<DeviceInfo>,
+
+    pub(crate) interface: Option<u8>,
+    pub(crate) serial_number: Option<String>,
+
+    pub(crate) parent: Option<String>,
+    pub(crate) parent_subsystem: Option<String>,
+    pub(crate) driver: Option<String>,
+}
+
+impl SerialPort {
+    /// Returns the kernel name of the port, such as `ttyUSB0` or `ttyS0`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the device file, such as `/dev/ttyUSB0`.
+    pub fn devnode(&self) -> String {
+        format!("/dev/{}", self.name)
+    }
+
+    /// Returns the PCI or USB device that provides the port, if any.
+    ///
+    /// Ports built into the platform, such as legacy UARTs, have no device.
+    pub fn device(&self) -> Option<&DeviceInfo> {
+        self.device.as_ref()
+    }
+
+    /// Returns the USB interface number the port is bound to, if known.
+    /// # Note
+    /// Always returns `None` for ports not provided by USB devices.
+    pub fn interface(&self) -> Option<u8> {
+        self.interface
+    }
+
+    /// Returns the serial number of the USB device, if known.
+    /// # Note
+    /// Always returns `None` for ports not provided by USB devices.
+    pub fn serial_number(&self) -> Option<&str> {
+        self.serial_number.as_deref()
+    }
+
+    /// Returns the name of the parent device of the port, if known.
+    ///
+    /// For platform ports this identifies the UART, such as `serial8250` or `00:01`.
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// Returns the subsystem of the parent device, such as `usb`, `pci`, `pnp` or `platform`.
+    pub fn parent_subsystem(&self) -> Option<&str> {
+        self.parent_subsystem.as_deref()
+    }
+
+    /// Returns the name of the driver bound to the parent device, if any.
+    pub fn driver(&self) -> Option<&str> {
+        self.driver.as_deref()
+    }
+}
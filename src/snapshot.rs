@@ -0,0 +1,740 @@
+use std::time::{
+    Duration,
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+use cfg_if::cfg_if;
+
+use crate::{
+    DeviceInfo,
+    Devices,
+    Error,
+};
+
+/// The snapshot format version written by this crate.
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+/// Information about the system a snapshot was taken on.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HostInfo {
+    pub(crate) hostname: Option<String>,
+    pub(crate) os: Option<String>,
+    pub(crate) kernel: Option<String>,
+    pub(crate) arch: Option<String>,
+    pub(crate) crate_version: Option<String>,
+}
+
+impl HostInfo {
+    /// Returns information about the current system.
+    pub fn current() -> Self {
+        cfg_if! {
+            if #[cfg(unix)] {
+                crate::linux::get_host()
+            } else {
+                Self {
+                    hostname: std::env::var("COMPUTERNAME").ok(),
+                    os: Some(std::env::consts::OS.to_owned()),
+                    kernel: None,
+                    arch: Some(std::env::consts::ARCH.to_owned()),
+                    crate_version: Some(env!("CARGO_PKG_VERSION").to_owned()),
+                }
+            }
+        }
+    }
+
+    /// Returns the host name, if known.
+    pub fn hostname(&self) -> Option<&str> {
+        self.hostname.as_deref()
+    }
+
+    /// Returns the operating system, such as `linux` or `windows`, if known.
+    pub fn os(&self) -> Option<&str> {
+        self.os.as_deref()
+    }
+
+    /// Returns the kernel release, if known.
+    /// # Note
+    /// Always returns `None` on Windows.
+    pub fn kernel(&self) -> Option<&str> {
+        self.kernel.as_deref()
+    }
+
+    /// Returns the CPU architecture, such as `x86_64` or `aarch64`, if known.
+    pub fn arch(&self) -> Option<&str> {
+        self.arch.as_deref()
+    }
+
+    /// Returns the version of this crate that took the snapshot, if known.
+    pub fn crate_version(&self) -> Option<&str> {
+        self.crate_version.as_deref()
+    }
+}
+
+/// A list of devices together with the system and time it was taken on.
+///
+/// With the `bincode` feature, use `Snapshot::encode` and `Snapshot::decode` to archive snapshots.
+/// Unlike encoding `Vec<DeviceInfo>` directly, the snapshot format is versioned
+/// and stays readable across crate releases.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Snapshot {
+    pub(crate) version: u16,
+    pub(crate) host: HostInfo,
+    pub(crate) timestamp: Option<u64>,
+    pub(crate) devices: Vec<DeviceInfo>,
+    pub(crate) skipped_devices: usize,
+}
+
+impl Snapshot {
+    /// Creates a snapshot of the given devices on the current system at the current time.
+    pub fn new(devices: Vec<DeviceInfo>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            host: HostInfo::current(),
+            timestamp,
+            devices,
+            skipped_devices: 0,
+        }
+    }
+
+    /// Creates a snapshot of all connected devices, as listed by [`Devices::all`].
+    /// # Errors
+    /// If the platform is unsupported or there is an issue retrieving the list of devices, an error is returned.
+    pub fn capture() -> Result<Self, Error> {
+        Ok(Self::new(Devices::all()?))
+    }
+
+    /// Returns the format version the snapshot was decoded from.
+    ///
+    /// Snapshots decoded from plain `Vec<DeviceInfo>` data written by version 0.5 have version `0`.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// Returns information about the system the snapshot was taken on.
+    pub fn host(&self) -> &HostInfo {
+        &self.host
+    }
+
+    /// Returns the time the snapshot was taken, if known.
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.timestamp
+            .and_then(|secs| UNIX_EPOCH.checked_add(Duration::from_secs(secs)))
+    }
+
+    /// Returns the devices in the snapshot.
+    pub fn devices(&self) -> &[DeviceInfo] {
+        &self.devices
+    }
+
+    /// Returns the number of devices that were left out while decoding the snapshot.
+    ///
+    /// Devices are left out if they were recorded by a newer version of this crate in a form this
+    /// version cannot read, or if they were recorded by version 0.5 with a USB device number.
+    pub fn skipped_devices(&self) -> usize {
+        self.skipped_devices
+    }
+
+    /// Consumes the snapshot, returning its devices.
+    pub fn into_devices(self) -> Vec<DeviceInfo> {
+        self.devices
+    }
+}
+
+#[cfg(feature = "bincode")]
+mod format {
+    //! Snapshot layout:
+    //!
+    //! - 8 bytes: [`MAGIC`]
+    //! - 2 bytes: format version, little-endian
+    //! - 2 bytes: oldest format version able to read the snapshot, little-endian
+    //! - [`Body`], encoded with the standard bincode configuration
+    //!
+    //! The host and each device are encoded into their own length-prefixed record,
+    //! and trailing bytes of a record are ignored. New fields are appended to the end
+    //! of a record without breaking older readers, which only requires bumping the format version.
+    //! The oldest readable version is raised only for changes older readers cannot skip.
+    //!
+    //! Data without [`MAGIC`] is decoded as a plain `Vec<DeviceInfo>` written by version 0.5
+    //! and migrated.
+    //!
+    //! The record types below are frozen: never change a record type once released,
+    //! add a new one and a migration instead.
+
+    use bincode::{
+        config,
+        Decode,
+        Encode,
+    };
+
+    use super::{
+        HostInfo,
+        Snapshot,
+        SNAPSHOT_VERSION,
+    };
+    use crate::{
+        DeviceInfo,
+        DevicePath,
+        Error,
+        PciResource,
+    };
+
+    const MAGIC: &[u8; 8] = b"DEVSNAP\0";
+
+    /// The oldest format version able to read snapshots written by this crate.
+    const MIN_READER_VERSION: u16 = 1;
+
+    const HEADER_LEN: usize = MAGIC.len() + 4;
+
+    #[derive(Decode, Encode)]
+    struct Body {
+        host: Vec<u8>,
+        timestamp: Option<u64>,
+        devices: Vec<Vec<u8>>,
+    }
+
+    #[derive(Decode, Encode)]
+    struct HostRecord {
+        hostname: Option<String>,
+        os: Option<String>,
+        kernel: Option<String>,
+        arch: Option<String>,
+        crate_version: Option<String>,
+    }
+
+    #[derive(Decode, Encode)]
+    enum PathRecord {
+        Pci {
+            domain: u32,
+            bus: u8,
+            slot: u8,
+            function: u8,
+        },
+        Usb {
+            bus: u8,
+            ports: Vec<u8>,
+        },
+        Platform(String),
+        I2c(String),
+        Spi(String),
+        Sdio(String),
+        Thunderbolt(String),
+    }
+
+    #[derive(Decode, Encode)]
+    struct ResourceRecord {
+        index: u8,
+        start: u64,
+        size: u64,
+        flags: u64,
+    }
+
+    #[derive(Decode, Encode)]
+    struct DeviceRecord {
+        path: PathRecord,
+        class: String,
+        vendor: String,
+        product: String,
+        manufacturer: Option<String>,
+        class_id: Option<u16>,
+        vendor_id: u16,
+        product_id: u16,
+        manufacturer_id: Option<u16>,
+        serial: Option<String>,
+        numa_node: Option<u16>,
+        local_cpus: Option<Vec<u64>>,
+        resources: Vec<ResourceRecord>,
+        compatible: Vec<String>,
+        acpi_hid: Option<String>,
+        udev_properties: Vec<(String, String)>,
+        udev_tags: Vec<String>,
+    }
+
+    /// `DevicePath` as encoded by version 0.5.
+    // USB devices are decoded to be skipped, so their bus and device number are never read
+    #[allow(dead_code)]
+    #[derive(Decode)]
+    enum LegacyPathRecord {
+        Pci { bus: u8, slot: u8, function: u8 },
+        Usb { bus: u8, device: u8 },
+    }
+
+    /// `DeviceInfo` as encoded by version 0.5.
+    #[derive(Decode)]
+    struct LegacyDeviceRecord {
+        path: LegacyPathRecord,
+        class: String,
+        vendor: String,
+        product: String,
+        manufacturer: Option<String>,
+        class_id: Option<u16>,
+        vendor_id: u16,
+        product_id: u16,
+        manufacturer_id: Option<u16>,
+    }
+
+    impl LegacyDeviceRecord {
+        /// Migrates the record to the current format.
+        ///
+        /// Returns `None` for USB devices, which were recorded by bus and device number.
+        /// Device numbers change whenever a device is reconnected and cannot be mapped to a port.
+        fn migrate(self) -> Option<DeviceRecord> {
+            let path = match self.path {
+                LegacyPathRecord::Pci {
+                    bus,
+                    slot,
+                    function,
+                } => PathRecord::Pci {
+                    domain: 0,
+                    bus,
+                    slot,
+                    function,
+                },
+                LegacyPathRecord::Usb { .. } => return None,
+            };
+
+            Some(DeviceRecord {
+                path,
+                class: self.class,
+                vendor: self.vendor,
+                product: self.product,
+                manufacturer: self.manufacturer,
+                class_id: self.class_id,
+                vendor_id: self.vendor_id,
+                product_id: self.product_id,
+                manufacturer_id: self.manufacturer_id,
+                serial: None,
+                numa_node: None,
+                local_cpus: None,
+                resources: Vec::new(),
+                compatible: Vec::new(),
+                acpi_hid: None,
+                udev_properties: Vec::new(),
+                udev_tags: Vec::new(),
+            })
+        }
+    }
+
+    impl From<&HostInfo> for HostRecord {
+        fn from(host: &HostInfo) -> Self {
+            Self {
+                hostname: host.hostname.clone(),
+                os: host.os.clone(),
+                kernel: host.kernel.clone(),
+                arch: host.arch.clone(),
+                crate_version: host.crate_version.clone(),
+            }
+        }
+    }
+
+    impl From<HostRecord> for HostInfo {
+        fn from(record: HostRecord) -> Self {
+            Self {
+                hostname: record.hostname,
+                os: record.os,
+                kernel: record.kernel,
+                arch: record.arch,
+                crate_version: record.crate_version,
+            }
+        }
+    }
+
+    impl From<&DeviceInfo> for DeviceRecord {
+        fn from(device: &DeviceInfo) -> Self {
+            let path = match &device.path {
+                DevicePath::PCI {
+                    domain,
+                    bus,
+                    slot,
+                    function,
+                } => PathRecord::Pci {
+                    domain: *domain,
+                    bus: *bus,
+                    slot: *slot,
+                    function: *function,
+                },
+                DevicePath::USB { bus, ports } => PathRecord::Usb {
+                    bus: *bus,
+                    ports: ports.clone(),
+                },
+                DevicePath::Platform { name } => PathRecord::Platform(name.clone()),
+                DevicePath::I2C { name } => PathRecord::I2c(name.clone()),
+                DevicePath::SPI { name } => PathRecord::Spi(name.clone()),
+                DevicePath::SDIO { name } => PathRecord::Sdio(name.clone()),
+                DevicePath::Thunderbolt { name } => PathRecord::Thunderbolt(name.clone()),
+            };
+
+            Self {
+                path,
+                class: device.class.clone(),
+                vendor: device.vendor.clone(),
+                product: device.product.clone(),
+                manufacturer: device.manufacturer.clone(),
+                class_id: device.class_id,
+                vendor_id: device.vendor_id,
+                product_id: device.product_id,
+                manufacturer_id: device.manufacturer_id,
+                serial: device.serial.clone(),
+                numa_node: device.numa_node,
+                local_cpus: device
+                    .local_cpus
+                    .as_ref()
+                    .map(|cpus| cpus.iter().map(|&c| c as u64).collect()),
+                resources: device
+                    .resources
+                    .iter()
+                    .map(|r| ResourceRecord {
+                        index: r.index,
+                        start: r.start,
+                        size: r.size,
+                        flags: r.flags,
+                    })
+                    .collect(),
+                compatible: device.compatible.clone(),
+                acpi_hid: device.acpi_hid.clone(),
+                udev_properties: device
+                    .udev_properties
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+                udev_tags: device.udev_tags.clone(),
+            }
+        }
+    }
+
+    impl TryFrom<DeviceRecord> for DeviceInfo {
+        type Error = Error;
+
+        fn try_from(record: DeviceRecord) -> Result<Self, Error> {
+            let path = match record.path {
+                PathRecord::Pci {
+                    domain,
+                    bus,
+                    slot,
+                    function,
+                } => DevicePath::PCI {
+                    domain,
+                    bus,
+                    slot,
+                    function,
+                },
+                PathRecord::Usb { bus, ports } => DevicePath::USB { bus, ports },
+                PathRecord::Platform(name) => DevicePath::Platform { name },
+                PathRecord::I2c(name) => DevicePath::I2C { name },
+                PathRecord::Spi(name) => DevicePath::SPI { name },
+                PathRecord::Sdio(name) => DevicePath::SDIO { name },
+                PathRecord::Thunderbolt(name) => DevicePath::Thunderbolt { name },
+            };
+
+            let local_cpus = record
+                .local_cpus
+                .map(|cpus| {
+                    cpus.into_iter()
+                        .map(|c| usize::try_from(c).map_err(|_| Error::ParseError))
+                        .collect::<Result<_, _>>()
+                })
+                .transpose()?;
+
+            Ok(Self {
+                path,
+                class: record.class,
+                vendor: record.vendor,
+                product: record.product,
+                manufacturer: record.manufacturer,
+                class_id: record.class_id,
+                vendor_id: record.vendor_id,
+                product_id: record.product_id,
+                manufacturer_id: record.manufacturer_id,
+                serial: record.serial,
+                numa_node: record.numa_node,
+                local_cpus,
+                resources: record
+                    .resources
+                    .into_iter()
+                    .map(|r| PciResource {
+                        index: r.index,
+                        start: r.start,
+                        size: r.size,
+                        flags: r.flags,
+                    })
+                    .collect(),
+                compatible: record.compatible,
+                acpi_hid: record.acpi_hid,
+                udev_properties: record.udev_properties.into_iter().collect(),
+                udev_tags: record.udev_tags,
+            })
+        }
+    }
+
+    fn encode_record<T: Encode>(record: &T) -> Result<Vec<u8>, Error> {
+        bincode::encode_to_vec(record, config::standard()).map_err(|_| Error::EncodeError)
+    }
+
+    fn decode_record<T: Decode<()>>(bytes: &[u8]) -> Result<T, Error> {
+        bincode::decode_from_slice(bytes, config::standard())
+            .map(|(record, _)| record)
+            .map_err(|_| Error::ParseError)
+    }
+
+    pub(super) fn encode(snapshot: &Snapshot) -> Result<Vec<u8>, Error> {
+        let body = Body {
+            host: encode_record(&HostRecord::from(&snapshot.host))?,
+            timestamp: snapshot.timestamp,
+            devices: snapshot
+                .devices
+                .iter()
+                .map(|d| encode_record(&DeviceRecord::from(d)))
+                .collect::<Result<_, _>>()?,
+        };
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&MIN_READER_VERSION.to_le_bytes());
+        bytes.extend(encode_record(&body)?);
+
+        Ok(bytes)
+    }
+
+    fn decode_legacy(bytes: &[u8]) -> Result<Snapshot, Error> {
+        let (records, len): (Vec<LegacyDeviceRecord>, _) =
+            bincode::decode_from_slice(bytes, config::standard()).map_err(|_| Error::ParseError)?;
+
+        if len != bytes.len() {
+            return Err(Error::ParseError);
+        }
+
+        let count = records.len();
+
+        let devices: Vec<DeviceInfo> = records
+            .into_iter()
+            .filter_map(LegacyDeviceRecord::migrate)
+            .map(DeviceInfo::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Snapshot {
+            version: 0,
+            host: HostInfo::default(),
+            timestamp: None,
+            skipped_devices: count - devices.len(),
+            devices,
+        })
+    }
+
+    pub(super) fn decode(bytes: &[u8]) -> Result<Snapshot, Error> {
+        let Some(header) = bytes.get(..HEADER_LEN).filter(|h| h.starts_with(MAGIC)) else {
+            return decode_legacy(bytes);
+        };
+
+        let version = u16::from_le_bytes([header[8], header[9]]);
+        let min_reader_version = u16::from_le_bytes([header[10], header[11]]);
+
+        if version == 0 || min_reader_version > SNAPSHOT_VERSION {
+            return Err(Error::UnsupportedVersion);
+        }
+
+        let body: Body = decode_record(&bytes[HEADER_LEN..])?;
+
+        // Devices added by newer versions, such as on new buses, are skipped
+        // rather than failing the whole snapshot.
+        let mut devices = Vec::with_capacity(body.devices.len());
+        let mut skipped_devices = 0;
+
+        for record in &body.devices {
+            match decode_record::<DeviceRecord>(record) {
+                Ok(record) => devices.push(DeviceInfo::try_from(record)?),
+                Err(_) if version > SNAPSHOT_VERSION => skipped_devices += 1,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Snapshot {
+            version,
+            host: decode_record::<HostRecord>(&body.host)?.into(),
+            timestamp: body.timestamp,
+            devices,
+            skipped_devices,
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::collections::BTreeMap;
+
+        use super::*;
+
+        fn device(path: DevicePath) -> DeviceInfo {
+            DeviceInfo {
+                path,
+                class: "Ethernet controller".to_owned(),
+                vendor: "Intel Corporation".to_owned(),
+                product: "I210 Gigabit Network Connection".to_owned(),
+                manufacturer: None,
+                class_id: Some(0x0200),
+                vendor_id: 0x8086,
+                product_id: 0x1533,
+                manufacturer_id: None,
+                serial: Some("00:1b:21:aa:bb:cc".to_owned()),
+                numa_node: Some(1),
+                local_cpus: Some(vec![0, 1, 2, 3]),
+                resources: vec![PciResource {
+                    index: 0,
+                    start: 0xf700_0000,
+                    size: 0x2_0000,
+                    flags: 0x0004_0200,
+                }],
+                compatible: Vec::new(),
+                acpi_hid: None,
+                udev_properties: BTreeMap::from([(
+                    "ID_NET_NAME_PATH".to_owned(),
+                    "enp3s0".to_owned(),
+                )]),
+                udev_tags: vec!["systemd".to_owned()],
+            }
+        }
+
+        #[test]
+        fn round_trip() {
+            let snapshot = Snapshot {
+                version: SNAPSHOT_VERSION,
+                host: HostInfo::default(),
+                timestamp: Some(1_700_000_000),
+                devices: vec![
+                    device(DevicePath::PCI {
+                        domain: 0x10000,
+                        bus: 3,
+                        slot: 0,
+                        function: 0,
+                    }),
+                    device(DevicePath::USB {
+                        bus: 1,
+                        ports: vec![2, 3],
+                    }),
+                ],
+                skipped_devices: 0,
+            };
+
+            assert_eq!(decode(&encode(&snapshot).unwrap()).unwrap(), snapshot);
+        }
+
+        /// `DevicePath` and `DeviceInfo` as defined by version 0.5.
+        mod legacy {
+            use bincode::Encode;
+
+            // Variant names match the 0.5 release
+            #[allow(clippy::upper_case_acronyms)]
+            #[derive(Encode)]
+            pub(super) enum DevicePath {
+                PCI { bus: u8, slot: u8, function: u8 },
+                USB { bus: u8, device: u8 },
+            }
+
+            #[derive(Encode)]
+            pub(super) struct DeviceInfo {
+                pub(super) path: DevicePath,
+                pub(super) class: String,
+                pub(super) vendor: String,
+                pub(super) product: String,
+                pub(super) manufacturer: Option<String>,
+                pub(super) class_id: Option<u16>,
+                pub(super) vendor_id: u16,
+                pub(super) product_id: u16,
+                pub(super) manufacturer_id: Option<u16>,
+            }
+        }
+
+        #[test]
+        fn decodes_version_0_5_device_lists() {
+            let devices = vec![
+                legacy::DeviceInfo {
+                    path: legacy::DevicePath::PCI {
+                        bus: 3,
+                        slot: 0,
+                        function: 0,
+                    },
+                    class: "Ethernet controller".to_owned(),
+                    vendor: "Intel Corporation".to_owned(),
+                    product: "I210 Gigabit Network Connection".to_owned(),
+                    manufacturer: None,
+                    class_id: Some(0x0200),
+                    vendor_id: 0x8086,
+                    product_id: 0x1533,
+                    manufacturer_id: None,
+                },
+                legacy::DeviceInfo {
+                    path: legacy::DevicePath::USB { bus: 1, device: 4 },
+                    class: "Human Interface Device".to_owned(),
+                    vendor: "Logitech, Inc.".to_owned(),
+                    product: "Unifying Receiver".to_owned(),
+                    manufacturer: Some("Logitech".to_owned()),
+                    class_id: Some(0x0300),
+                    vendor_id: 0x046d,
+                    product_id: 0xc52b,
+                    manufacturer_id: None,
+                },
+            ];
+
+            let bytes = bincode::encode_to_vec(&devices, config::standard()).unwrap();
+            let snapshot = decode(&bytes).unwrap();
+
+            let expected = DeviceInfo {
+                serial: None,
+                numa_node: None,
+                local_cpus: None,
+                resources: Vec::new(),
+                udev_properties: BTreeMap::new(),
+                udev_tags: Vec::new(),
+                ..device(DevicePath::PCI {
+                    domain: 0,
+                    bus: 3,
+                    slot: 0,
+                    function: 0,
+                })
+            };
+
+            assert_eq!(snapshot.version(), 0);
+            assert_eq!(snapshot.host(), &HostInfo::default());
+            assert_eq!(snapshot.devices(), [expected]);
+            assert_eq!(snapshot.skipped_devices(), 1);
+        }
+
+        #[test]
+        fn rejects_newer_readers_only() {
+            let mut bytes = encode(&Snapshot::new(Vec::new())).unwrap();
+            bytes[10..12].copy_from_slice(&(SNAPSHOT_VERSION + 1).to_le_bytes());
+
+            assert!(matches!(decode(&bytes), Err(Error::UnsupportedVersion)));
+        }
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl Snapshot {
+    /// Encodes the snapshot in the current snapshot format.
+    /// # Errors
+    /// If the snapshot could not be encoded, an error is returned.
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        format::encode(self)
+    }
+
+    /// Decodes a snapshot written by this or another version of this crate.
+    ///
+    /// Plain `Vec<DeviceInfo>` data encoded with the standard bincode configuration by version 0.5
+    /// is also accepted, and decoded without host information or timestamp.
+    /// # Note
+    /// Version 0.5 recorded USB devices by bus and device number, which cannot be mapped to the
+    /// port the device was on. Those USB devices are left out, and counted by
+    /// [`Snapshot::skipped_devices`].
+    /// # Errors
+    /// If the data is malformed or requires a newer version of this crate, an error is returned.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        format::decode(bytes)
+    }
+}
@@ -0,0 +1,146 @@
+use crate::info::DeviceInfo;
+
+/// A PCM device of a sound card.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PcmDevice {
+    pub(crate) card: u32,
+    pub(crate) device: u32,
+    pub(crate) name: String,
+    pub(crate) playback: bool,
+    pub(crate) capture: bool,
+}
+
+impl PcmDevice {
+    /// Returns the index of the PCM device within its card.
+    pub fn device(&self) -> u32 {
+        self.device
+    }
+
+    /// Returns the name of the PCM device, such as `ALC892 Analog`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the PCM device supports playback.
+    pub fn playback(&self) -> bool {
+        self.playback
+    }
+
+    /// Returns `true` if the PCM device supports capture.
+    pub fn capture(&self) -> bool {
+        self.capture
+    }
+
+    /// Returns the ALSA device name, such as `hw:0,3`.
+    pub fn alsa_name(&self) -> String {
+        format!("hw:{},{}", self.card, self.device)
+    }
+}
+
+/// An HD Audio codec of a sound card.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HdaCodec {
+    pub(crate) address: u32,
+    pub(crate) vendor_id: u32,
+    pub(crate) subsystem_id: u32,
+    pub(crate) revision_id: u32,
+    pub(crate) vendor_name: Option<String>,
+    pub(crate) chip_name: Option<String>,
+}
+
+impl HdaCodec {
+    /// Returns the address of the codec on the HD Audio link.
+    pub fn address(&self) -> u32 {
+        self.address
+    }
+
+    /// Returns the codec id, with the vendor in the upper 16 bits, such as `0x10ec0892`.
+    pub fn vendor_id(&self) -> u32 {
+        self.vendor_id
+    }
+
+    /// Returns the subsystem id, identifying the board the codec is used in.
+    pub fn subsystem_id(&self) -> u32 {
+        self.subsystem_id
+    }
+
+    /// Returns the revision id of the codec.
+    pub fn revision_id(&self) -> u32 {
+        self.revision_id
+    }
+
+    /// Returns the name of the codec vendor, such as `Realtek`, if known.
+    pub fn vendor_name(&self) -> Option<&str> {
+        self.vendor_name.as_deref()
+    }
+
+    /// Returns the name of the codec, such as `ALC892`, if known.
+    pub fn chip_name(&self) -> Option<&str> {
+        self.chip_name.as_deref()
+    }
+}
+
+/// A sound card, along with the device that provides it.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SoundCard {
+    pub(crate) index: u32,
+    pub(crate) device: Option<DeviceInfo>,
+
+    pub(crate) id: String,
+    pub(crate) driver: String,
+    pub(crate) name: String,
+    pub(crate) long_name: String,
+
+    pub(crate) pcms: Vec<PcmDevice>,
+    pub(crate) codecs: Vec<HdaCodec>,
+}
+
+impl SoundCard {
+    /// Returns the index of the card.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the PCI, USB or platform device that provides the card, if known.
+    pub fn device(&self) -> Option<&DeviceInfo> {
+        self.device.as_ref()
+    }
+
+    /// Returns the identifier of the card, such as `PCH`.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the name of the card's driver, such as `HDA-Intel` or `USB-Audio`.
+    pub fn driver(&self) -> &str {
+        &self.driver
+    }
+
+    /// Returns the short name of the card.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the long name of the card, which usually includes its location.
+    pub fn long_name(&self) -> &str {
+        &self.long_name
+    }
+
+    /// Returns the PCM devices of the card.
+    pub fn pcms(&self) -> &[PcmDevice] {
+        &self.pcms
+    }
+
+    /// Returns the HD Audio codecs of the card.
+    /// # Note
+    /// Always empty for cards that are not HD Audio controllers.
+    pub fn codecs(&self) -> &[HdaCodec] {
+        &self.codecs
+    }
+}
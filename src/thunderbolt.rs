@@ -0,0 +1,134 @@
+use crate::info::DeviceInfo;
+
+/// Security level of a Thunderbolt domain.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ThunderboltSecurity {
+    /// All devices are connected automatically.
+    None,
+
+    /// Devices must be authorized by the user.
+    User,
+
+    /// Devices must be authorized by the user and verified with a challenge key.
+    Secure,
+
+    /// Only display tunnels are created.
+    DpOnly,
+
+    /// Only display and USB tunnels are created.
+    UsbOnly,
+
+    /// PCI tunneling is disabled.
+    NoPcie,
+
+    /// The security level could not be determined.
+    Unknown,
+}
+
+/// Authorization state of a Thunderbolt device.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ThunderboltAuthorization {
+    /// The device is not authorized, and no tunnels are created.
+    Unauthorized,
+
+    /// The device is authorized.
+    Authorized,
+
+    /// The device is authorized and was verified with a challenge key.
+    AuthorizedWithKey,
+}
+
+/// A Thunderbolt or USB4 router, such as a host controller or a dock.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ThunderboltDevice {
+    pub(crate) device: DeviceInfo,
+
+    pub(crate) unique_id: Option<String>,
+    pub(crate) authorization: ThunderboltAuthorization,
+    pub(crate) generation: Option<u8>,
+    pub(crate) host: bool,
+
+    pub(crate) tunneled: Vec<DeviceInfo>,
+}
+
+impl ThunderboltDevice {
+    /// Returns the router as a device, including its vendor and device names.
+    pub fn device(&self) -> &DeviceInfo {
+        &self.device
+    }
+
+    /// Returns the unique id of the router, if known.
+    pub fn unique_id(&self) -> Option<&str> {
+        self.unique_id.as_deref()
+    }
+
+    /// Returns the authorization state of the router.
+    pub fn authorization(&self) -> ThunderboltAuthorization {
+        self.authorization
+    }
+
+    /// Returns the Thunderbolt generation of the router, such as `3`, or `4` for USB4, if known.
+    pub fn generation(&self) -> Option<u8> {
+        self.generation
+    }
+
+    /// Returns `true` if the router is the host controller of its domain.
+    pub fn is_host(&self) -> bool {
+        self.host
+    }
+
+    /// Returns the PCI devices tunneled through the router, such as the PCI bridges and USB controllers of a dock.
+    /// # Note
+    /// On Linux the kernel does not link routers to the PCI Express switches of docks, so they are paired by their place in the chain of connected devices.
+    /// Devices behind routers that cannot be told apart this way are left out.
+    pub fn tunneled(&self) -> &[DeviceInfo] {
+        &self.tunneled
+    }
+}
+
+/// A Thunderbolt domain, made up of a host controller and the devices connected to it.
+#[cfg_attr(feature = "bincode", derive(bincode::Decode, bincode::Encode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ThunderboltDomain {
+    pub(crate) name: String,
+    pub(crate) controller: Option<DeviceInfo>,
+
+    pub(crate) security: ThunderboltSecurity,
+    pub(crate) iommu_dma_protection: bool,
+
+    pub(crate) devices: Vec<ThunderboltDevice>,
+}
+
+impl ThunderboltDomain {
+    /// Returns the kernel name of the domain, such as `domain0`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the PCI device of the host controller, if known.
+    pub fn controller(&self) -> Option<&DeviceInfo> {
+        self.controller.as_ref()
+    }
+
+    /// Returns the security level of the domain.
+    pub fn security(&self) -> ThunderboltSecurity {
+        self.security
+    }
+
+    /// Returns `true` if the IOMMU protects the system from DMA attacks by connected devices.
+    pub fn iommu_dma_protection(&self) -> bool {
+        self.iommu_dma_protection
+    }
+
+    /// Returns the routers in the domain, including the host router.
+    pub fn devices(&self) -> &[ThunderboltDevice] {
+        &self.devices
+    }
+}
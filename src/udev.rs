@@ -0,0 +1,614 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    str::FromStr,
+};
+
+use cfg_if::cfg_if;
+
+use crate::{
+    error::{
+        ParseRulesError,
+        RuleErrorKind,
+    },
+    DevicePath,
+    Error,
+};
+
+/// A set of udev rules.
+///
+/// Supports a subset of the udev rules syntax, enough to check which rules apply to a device
+/// and what they would do, without running udev:
+/// - match keys: `ACTION`, `DEVPATH`, `KERNEL`, `SUBSYSTEM`, `DRIVER`, `ATTR{file}`, `ENV{key}`, `TAG`,
+///   and `KERNELS`, `SUBSYSTEMS`, `DRIVERS`, `ATTRS{file}` which match the device or one of its parents
+/// - assignment keys: `ENV{key}`, `SYMLINK`, `NAME`, `OWNER`, `GROUP`, `MODE`, `TAG`, `RUN`,
+///   `ATTR{file}`, `LABEL` and `GOTO`
+/// - patterns with `*`, `?`, `[...]` and `|` alternatives
+/// - substitutions such as `%k`, `$kernel`, `%n`, `%p`, `$attr{file}` and `$env{key}` in assigned values
+///
+/// Devices are matched as if they were being added, so `ACTION` is `add`.
+/// `OPTIONS` assignments are accepted and ignored.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct UdevRules {
+    pub(crate) rules: Vec<UdevRule>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) struct UdevRule {
+    pub(crate) line: usize,
+    pub(crate) matches: Vec<RuleMatch>,
+    pub(crate) assignments: Vec<RuleAssignment>,
+    pub(crate) label: Option<String>,
+    pub(crate) goto: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) enum MatchKey {
+    Action,
+    DevPath,
+    Kernel,
+    Subsystem,
+    Driver,
+    Attr(String),
+    Env(String),
+    Tag,
+    Kernels,
+    Subsystems,
+    Drivers,
+    Attrs(String),
+}
+
+impl MatchKey {
+    /// Returns `true` if the key matches the device or one of its parents.
+    pub(crate) fn is_parent_key(&self) -> bool {
+        matches!(self, Self::Kernels | Self::Subsystems | Self::Drivers | Self::Attrs(_))
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) struct RuleMatch {
+    pub(crate) key: MatchKey,
+    pub(crate) negated: bool,
+    pub(crate) value: String,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) enum AssignKey {
+    Env(String),
+    Attr(String),
+    Symlink,
+    Name,
+    Owner,
+    Group,
+    Mode,
+    Tag,
+    Run,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) enum AssignOp {
+    Set,
+    Add,
+    Remove,
+    Final,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) struct RuleAssignment {
+    pub(crate) key: AssignKey,
+    pub(crate) op: AssignOp,
+    pub(crate) value: String,
+}
+
+/// The outcome of applying udev rules to a device.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct UdevEvaluation {
+    pub(crate) matched_lines: Vec<usize>,
+    pub(crate) properties: BTreeMap<String, String>,
+    pub(crate) symlinks: Vec<String>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) attributes: BTreeMap<String, String>,
+    pub(crate) run: Vec<String>,
+    pub(crate) name: Option<String>,
+    pub(crate) owner: Option<String>,
+    pub(crate) group: Option<String>,
+    pub(crate) mode: Option<String>,
+}
+
+impl UdevEvaluation {
+    /// Returns the line numbers of the rules that applied, in order, starting from `1`.
+    pub fn matched_lines(&self) -> &[usize] {
+        &self.matched_lines
+    }
+
+    /// Returns the device properties, including those from the kernel and those set with `ENV{key}=`.
+    pub fn properties(&self) -> &BTreeMap<String, String> {
+        &self.properties
+    }
+
+    /// Returns the symbolic links that would be created, relative to `/dev`.
+    pub fn symlinks(&self) -> &[String] {
+        &self.symlinks
+    }
+
+    /// Returns the tags of the device.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns the sysfs attributes that would be written, by file name.
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+
+    /// Returns the programs that would be run.
+    pub fn run(&self) -> &[String] {
+        &self.run
+    }
+
+    /// Returns the assigned network interface name, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the assigned owner of the device node, if any.
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Returns the assigned group of the device node, if any.
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Returns the assigned permissions of the device node, if any.
+    pub fn mode(&self) -> Option<&str> {
+        self.mode.as_deref()
+    }
+}
+
+impl UdevRules {
+    /// Parses udev rules.
+    ///
+    /// Comments, empty lines and lines continued with `\` are supported.
+    /// Rules from several files can be combined by concatenating them in order.
+    /// # Errors
+    /// If a rule is malformed or uses an unsupported key, an error with its line number is returned.
+    pub fn parse(s: &str) -> Result<Self, ParseRulesError> {
+        let mut rules = Vec::new();
+        let mut pending = String::new();
+        let mut start = 0;
+
+        for (i, line) in s.lines().enumerate() {
+            if pending.is_empty() {
+                start = i + 1;
+            }
+
+            if let Some(continued) = line.strip_suffix('\\') {
+                pending.push_str(continued);
+                continue;
+            }
+
+            pending.push_str(line);
+
+            let text = std::mem::take(&mut pending);
+            let text = text.trim();
+
+            if !text.is_empty() && !text.starts_with('#') {
+                rules.push(parse_rule(text, start)?);
+            }
+        }
+
+        if !pending.trim().is_empty() {
+            rules.push(parse_rule(pending.trim(), start)?);
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Returns the number of rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns `true` if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Applies the rules to a sysfs device directory.
+    ///
+    /// The directory may be part of a copy of sysfs, such as a tree captured for testing,
+    /// in which case parents are looked up within the copy.
+    /// # Errors
+    /// If the platform is unsupported or the directory is not a device, an error is returned.
+    pub fn evaluate(&self, dir: &Path) -> Result<UdevEvaluation, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                crate::linux::evaluate_udev_rules(self, dir)
+            } else {
+                let _ = dir;
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Applies the rules to a connected device.
+    /// # Errors
+    /// If the platform is unsupported or the device does not exist, an error is returned.
+    pub fn evaluate_device(&self, path: &DevicePath) -> Result<UdevEvaluation, Error> {
+        self.evaluate(&path.to_sysfs()?)
+    }
+}
+
+impl FromStr for UdevRules {
+    type Err = ParseRulesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// The record udev keeps for a device in its database, under `/run/udev/data`.
+///
+/// Holds the properties set by udev rules and hardware database lookups, such as
+/// `ID_MODEL_FROM_DATABASE`, `ID_SERIAL` and `ID_PATH`, which are not available from sysfs.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct UdevData {
+    pub(crate) properties: BTreeMap<String, String>,
+    pub(crate) symlinks: Vec<String>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) current_tags: Vec<String>,
+    pub(crate) link_priority: Option<i32>,
+    pub(crate) initialized: Option<u64>,
+}
+
+impl UdevData {
+    /// Parses the contents of a udev database file.
+    ///
+    /// Each line holds a one-letter type, a colon and a value. Unknown types are ignored.
+    /// # Errors
+    /// If a line is malformed, an error is returned.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let mut data = Self::default();
+
+        for line in s.lines().filter(|l| !l.is_empty()) {
+            let (kind, value) = line.split_once(':').ok_or(Error::ParseError)?;
+
+            match kind {
+                "E" => {
+                    let (key, value) = value.split_once('=').ok_or(Error::ParseError)?;
+                    data.properties.insert(key.to_owned(), value.to_owned());
+                }
+                "S" => data.symlinks.push(value.to_owned()),
+                "G" => data.tags.push(value.to_owned()),
+                "Q" => data.current_tags.push(value.to_owned()),
+                "L" => data.link_priority = Some(value.parse().map_err(|_| Error::ParseError)?),
+                "I" => data.initialized = Some(value.parse().map_err(|_| Error::ParseError)?),
+                _ if kind.len() == 1 => {}
+                _ => return Err(Error::ParseError),
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Reads the udev database record of a sysfs device directory.
+    /// # Errors
+    /// If the platform is unsupported, or udev has no record of the device, an error is returned.
+    pub fn read(dir: &Path) -> Result<Self, Error> {
+        cfg_if! {
+            if #[cfg(unix)] {
+                crate::linux::read_udev_data(dir)
+            } else {
+                let _ = dir;
+                Err(Error::UnsupportedPlatform)
+            }
+        }
+    }
+
+    /// Reads the udev database record of a connected device.
+    /// # Errors
+    /// If the platform is unsupported, the device does not exist, or udev has no record of it,
+    /// an error is returned.
+    pub fn read_device(path: &DevicePath) -> Result<Self, Error> {
+        Self::read(&path.to_sysfs()?)
+    }
+
+    /// Returns the device properties, such as `ID_SERIAL` or `ID_PATH`.
+    pub fn properties(&self) -> &BTreeMap<String, String> {
+        &self.properties
+    }
+
+    /// Returns the value of a device property, if set.
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.get(key).map(String::as_str)
+    }
+
+    /// Returns the symbolic links to the device node, relative to `/dev`.
+    pub fn symlinks(&self) -> &[String] {
+        &self.symlinks
+    }
+
+    /// Returns the tags of the device, including tags that were since removed.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns the tags of the device set by the latest event.
+    pub fn current_tags(&self) -> &[String] {
+        &self.current_tags
+    }
+
+    /// Returns the priority of the device's symbolic links, if set.
+    pub fn link_priority(&self) -> Option<i32> {
+        self.link_priority
+    }
+
+    /// Returns when udev first processed the device, in microseconds of monotonic time, if known.
+    pub fn initialized(&self) -> Option<u64> {
+        self.initialized
+    }
+}
+
+impl FromStr for UdevData {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Splits a key such as `ATTRS{idVendor}` into its name and attribute.
+fn split_key(key: &str) -> Result<(&str, Option<&str>), RuleErrorKind> {
+    match key.split_once('{') {
+        Some((name, rest)) => {
+            let attr = rest
+                .strip_suffix('}')
+                .filter(|a| !a.is_empty())
+                .ok_or_else(|| RuleErrorKind::Malformed(key.to_owned()))?;
+
+            Ok((name, Some(attr)))
+        }
+        None => Ok((key, None)),
+    }
+}
+
+fn match_key(name: &str, attr: Option<&str>) -> Option<MatchKey> {
+    Some(match (name, attr) {
+        ("ACTION", None) => MatchKey::Action,
+        ("DEVPATH", None) => MatchKey::DevPath,
+        ("KERNEL", None) => MatchKey::Kernel,
+        ("SUBSYSTEM", None) => MatchKey::Subsystem,
+        ("DRIVER", None) => MatchKey::Driver,
+        ("ATTR", Some(attr)) => MatchKey::Attr(attr.to_owned()),
+        ("ENV", Some(key)) => MatchKey::Env(key.to_owned()),
+        ("TAG", None) => MatchKey::Tag,
+        ("KERNELS", None) => MatchKey::Kernels,
+        ("SUBSYSTEMS", None) => MatchKey::Subsystems,
+        ("DRIVERS", None) => MatchKey::Drivers,
+        ("ATTRS", Some(attr)) => MatchKey::Attrs(attr.to_owned()),
+        _ => return None,
+    })
+}
+
+fn assign_key(name: &str, attr: Option<&str>) -> Option<AssignKey> {
+    Some(match (name, attr) {
+        ("ENV", Some(key)) => AssignKey::Env(key.to_owned()),
+        ("ATTR", Some(attr)) => AssignKey::Attr(attr.to_owned()),
+        ("SYMLINK", None) => AssignKey::Symlink,
+        ("NAME", None) => AssignKey::Name,
+        ("OWNER", None) => AssignKey::Owner,
+        ("GROUP", None) => AssignKey::Group,
+        ("MODE", None) => AssignKey::Mode,
+        ("TAG", None) => AssignKey::Tag,
+        ("RUN", _) => AssignKey::Run,
+        _ => return None,
+    })
+}
+
+/// Returns the length of the key at the start of the text, including any `{attribute}`.
+fn key_len(text: &str) -> Option<usize> {
+    let mut end = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+
+    if end == 0 {
+        return None;
+    }
+
+    if text[end..].starts_with('{') {
+        end += text[end..].find('}')? + 1;
+    }
+
+    Some(end)
+}
+
+/// Reads a quoted value, returning it and the rest of the text.
+fn read_value(text: &str) -> Result<(String, &str), RuleErrorKind> {
+    let rest = text.strip_prefix('"').ok_or(RuleErrorKind::UnquotedValue)?;
+    let mut value = String::new();
+    let mut chars = rest.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &rest[i + 1..])),
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    if escaped != '"' {
+                        value.push('\\');
+                    }
+
+                    value.push(escaped);
+                }
+            }
+            _ => value.push(c),
+        }
+    }
+
+    Err(RuleErrorKind::UnterminatedValue)
+}
+
+fn parse_rule(text: &str, line: usize) -> Result<UdevRule, ParseRulesError> {
+    let error = |kind| ParseRulesError { line, kind };
+
+    let mut rule = UdevRule {
+        line,
+        matches: Vec::new(),
+        assignments: Vec::new(),
+        label: None,
+        goto: None,
+    };
+
+    let mut rest = text;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+
+        if rest.is_empty() {
+            break;
+        }
+
+        let key_end =
+            key_len(rest).ok_or_else(|| error(RuleErrorKind::Malformed(rest.to_owned())))?;
+
+        let key = &rest[..key_end];
+        let after_key = rest[key_end..].trim_start();
+
+        let (op, after_op) = ["==", "!=", "+=", "-=", ":=", "="]
+            .into_iter()
+            .find_map(|op| after_key.strip_prefix(op).map(|r| (op, r)))
+            .ok_or_else(|| error(RuleErrorKind::Malformed(rest.to_owned())))?;
+
+        let (value, after_value) = read_value(after_op.trim_start()).map_err(error)?;
+        rest = after_value;
+
+        let (name, attr) = split_key(key).map_err(error)?;
+
+        if op == "==" || op == "!=" {
+            let key = match_key(name, attr)
+                .ok_or_else(|| error(RuleErrorKind::UnsupportedKey(key.to_owned())))?;
+
+            rule.matches.push(RuleMatch {
+                key,
+                negated: op == "!=",
+                value,
+            });
+
+            continue;
+        }
+
+        match name {
+            "LABEL" => rule.label = Some(value),
+            "GOTO" => rule.goto = Some(value),
+            "OPTIONS" => {}
+            _ => {
+                let key = assign_key(name, attr)
+                    .ok_or_else(|| error(RuleErrorKind::UnsupportedKey(key.to_owned())))?;
+
+                let op = match op {
+                    "+=" => AssignOp::Add,
+                    "-=" => AssignOp::Remove,
+                    ":=" => AssignOp::Final,
+                    _ => AssignOp::Set,
+                };
+
+                rule.assignments.push(RuleAssignment { key, op, value });
+            }
+        }
+    }
+
+    Ok(rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(s: &str) -> UdevRule {
+        let mut rules = UdevRules::parse(s).unwrap().rules;
+        assert_eq!(rules.len(), 1);
+        rules.remove(0)
+    }
+
+    #[test]
+    fn joins_continued_lines() {
+        let rules = UdevRules::parse(concat!(
+            "# Logitech receivers\n",
+            "\n",
+            "SUBSYSTEM==\"usb\", \\\n",
+            "  ATTRS{idVendor}==\"046d\", \\\n",
+            "  TAG+=\"uaccess\"\n",
+            "KERNEL==\"hidraw*\", MODE=\"0660\"\n",
+        ))
+        .unwrap();
+
+        assert_eq!(rules.len(), 2);
+
+        let rule = &rules.rules[0];
+        assert_eq!(rule.line, 3);
+        assert_eq!(rule.matches.len(), 2);
+        assert_eq!(rule.matches[1].key, MatchKey::Attrs("idVendor".to_owned()));
+        assert_eq!(rule.assignments[0].key, AssignKey::Tag);
+
+        assert_eq!(rules.rules[1].line, 6);
+    }
+
+    #[test]
+    fn unescapes_quotes_only() {
+        let rule = parse_one(r#"ENV{ID_NAME}="say \"hi\"", RUN+="/bin/sh -c 'echo a\tb'""#);
+
+        assert_eq!(rule.assignments[0].value, r#"say "hi""#);
+        assert_eq!(rule.assignments[1].value, r"/bin/sh -c 'echo a\tb'");
+    }
+
+    #[test]
+    fn parses_operators() {
+        let rule = parse_one(concat!(
+            r#"KERNEL!="sd*", MODE:="0600", SYMLINK+="disk", TAG-="seat", "#,
+            r#"OWNER="root", OPTIONS+="last_rule""#,
+        ));
+
+        assert!(rule.matches[0].negated);
+
+        let ops: Vec<_> = rule.assignments.iter().map(|a| a.op).collect();
+        assert_eq!(ops, [AssignOp::Final, AssignOp::Add, AssignOp::Remove, AssignOp::Set]);
+    }
+
+    #[test]
+    fn parses_goto_and_label() {
+        let rules = UdevRules::parse(concat!(
+            "SUBSYSTEM!=\"usb\", GOTO=\"usb_end\"\n",
+            "LABEL=\"usb_end\"\n",
+        ))
+        .unwrap();
+
+        let goto = &rules.rules[0];
+        assert_eq!(goto.goto.as_deref(), Some("usb_end"));
+        assert!(goto.assignments.is_empty());
+
+        let label = &rules.rules[1];
+        assert_eq!(label.label.as_deref(), Some("usb_end"));
+        assert!(label.matches.is_empty() && label.assignments.is_empty());
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        let error = |s: &str| UdevRules::parse(s).unwrap_err();
+
+        let e = error("KERNEL==\"sda\"\n\nSUBSYSTEM==\"block\", \\\n  NAME=sda\n");
+        assert_eq!(e.line(), 3);
+        assert_eq!(e.kind(), &RuleErrorKind::UnquotedValue);
+
+        let e = error("KERNEL==\"sda");
+        assert_eq!(e.kind(), &RuleErrorKind::UnterminatedValue);
+
+        let e = error("LABEL==\"end\"");
+        assert_eq!(e.kind(), &RuleErrorKind::UnsupportedKey("LABEL".to_owned()));
+
+        let e = error("ATTRS{}==\"1\"");
+        assert_eq!(e.kind(), &RuleErrorKind::Malformed("ATTRS{}".to_owned()));
+    }
+}
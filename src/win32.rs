@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     mem,
     ptr,
 };
@@ -268,16 +269,6 @@ macro_rules! ok_or_next {
     };
 }
 
-macro_rules! or_next {
-    ($e:expr) => {
-        if let Some(value) = $e {
-            value
-        } else {
-            continue;
-        }
-    };
-}
-
 pub(crate) fn get_pci() -> Result<Vec<DeviceInfo>, Error> {
     let mut devices = Vec::new();
 
@@ -300,16 +291,7 @@ pub(crate) fn get_pci() -> Result<Vec<DeviceInfo>, Error> {
         // Path (Location)
 
         let location: String = info.get_property(DEVPKEY_Device_LocationInfo)?;
-        let mut iter = location.split(", ");
-
-        let (_, bus) = iter.next().ok_or(Error::ParseError)?.split_at(8);
-        let bus = bus.parse::<u8>().map_err(|_| Error::ParseError)?;
-
-        let (_, slot) = iter.next().ok_or(Error::ParseError)?.split_at(7);
-        let slot = slot.parse::<u8>().map_err(|_| Error::ParseError)?;
-
-        let (_, function) = iter.next().ok_or(Error::ParseError)?.split_at(9);
-        let function = function.parse::<u8>().map_err(|_| Error::ParseError)?;
+        let path = DevicePath::from_windows_location(&location).map_err(|_| Error::ParseError)?;
 
         // Class Name, Vendor Name, Product Name
 
@@ -323,11 +305,7 @@ pub(crate) fn get_pci() -> Result<Vec<DeviceInfo>, Error> {
         let (vendor_id, product_id) = parse_hwids(&hwids)?;
 
         devices.push(DeviceInfo {
-            path: DevicePath::PCI {
-                bus,
-                slot,
-                function,
-            },
+            path,
             class,
             vendor,
             product,
@@ -336,6 +314,14 @@ pub(crate) fn get_pci() -> Result<Vec<DeviceInfo>, Error> {
             vendor_id,
             product_id,
             manufacturer_id: None,
+            serial: None,
+            numa_node: None,
+            local_cpus: None,
+            resources: Vec::new(),
+            compatible: Vec::new(),
+            acpi_hid: None,
+            udev_properties: BTreeMap::new(),
+            udev_tags: Vec::new(),
         });
     }
 
@@ -364,14 +350,7 @@ pub(crate) fn get_usb() -> Result<Vec<DeviceInfo>, Error> {
         // Path (Location)
 
         let location: String = ok_or_next!(info.get_property(DEVPKEY_Device_LocationInfo));
-
-        let (device, bus) = or_next!(location.split_once('.'));
-
-        let (_, device) = device.split_at(6);
-        let device = ok_or_next!(u8::from_str_radix(device, 16));
-
-        let (_, bus) = bus.split_at(5);
-        let bus = ok_or_next!(u8::from_str_radix(bus, 16));
+        let path = ok_or_next!(DevicePath::from_windows_location(&location));
 
         // Class Name, Vendor Name, Product Name, Manufacturer Name
 
@@ -386,7 +365,7 @@ pub(crate) fn get_usb() -> Result<Vec<DeviceInfo>, Error> {
         let (vendor_id, product_id) = ok_or_next!(parse_hwids(&hwids));
 
         devices.push(DeviceInfo {
-            path: DevicePath::USB { bus, device },
+            path,
             class,
             vendor,
             product,
@@ -395,6 +374,14 @@ pub(crate) fn get_usb() -> Result<Vec<DeviceInfo>, Error> {
             vendor_id,
             product_id,
             manufacturer_id: None,
+            serial: None,
+            numa_node: None,
+            local_cpus: None,
+            resources: Vec::new(),
+            compatible: Vec::new(),
+            acpi_hid: None,
+            udev_properties: BTreeMap::new(),
+            udev_tags: Vec::new(),
         });
     }
 